@@ -0,0 +1,218 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::models::CommentInfo;
+
+const BASELINE_VERSION: u32 = 1;
+const PREVIEW_LENGTH: usize = 80;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BaselineEntry {
+    fingerprint: String,
+    path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    symbol: Option<String>,
+    text: String,
+    #[serde(default = "default_count")]
+    count: usize,
+}
+
+fn default_count() -> usize {
+    1
+}
+
+impl BaselineEntry {
+    pub fn new(relative_path: &Path, comment: &CommentInfo) -> Self {
+        let text = comment.normalised_text();
+        let preview = match text.lines().next() {
+            Some(line) if line.chars().count() > PREVIEW_LENGTH => {
+                format!(
+                    "{}...",
+                    line.chars().take(PREVIEW_LENGTH).collect::<String>()
+                )
+            }
+            Some(line) => line.to_owned(),
+            None => String::new(),
+        };
+        Self {
+            fingerprint: fingerprint(relative_path, comment),
+            path: normalise_path(relative_path),
+            symbol: comment.symbol().map(str::to_owned),
+            text: preview,
+            count: 1,
+        }
+    }
+
+    pub fn fingerprint(&self) -> &str {
+        &self.fingerprint
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn symbol(&self) -> Option<&str> {
+        self.symbol.as_deref()
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BaselineFile {
+    version: u32,
+    entries: Vec<BaselineEntry>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    densities: BTreeMap<String, f64>,
+}
+
+pub struct Baseline {
+    path: PathBuf,
+    entries: Vec<BaselineEntry>,
+    densities: BTreeMap<String, f64>,
+}
+
+impl Baseline {
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.is_file() {
+            return Ok(Self {
+                path: path.to_path_buf(),
+                entries: Vec::new(),
+                densities: BTreeMap::new(),
+            });
+        }
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("failed to read baseline {}", path.display()))?;
+        let file = serde_json::from_str::<BaselineFile>(&content)
+            .with_context(|| format!("failed to parse baseline {}", path.display()))?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            entries: file.entries,
+            densities: file.densities,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn entries(&self) -> &[BaselineEntry] {
+        &self.entries
+    }
+
+    pub fn density(&self, relative_path: &Path) -> Option<f64> {
+        self.densities.get(&normalise_path(relative_path)).copied()
+    }
+
+    pub fn replace(
+        &mut self,
+        keep: impl Fn(&str) -> bool,
+        entries: Vec<BaselineEntry>,
+        densities: Vec<(PathBuf, f64)>,
+    ) {
+        self.entries.retain(|e| keep(&e.path));
+        self.densities.retain(|path, _| keep(path));
+        for (path, density) in densities {
+            self.densities.insert(normalise_path(&path), density);
+        }
+        for entry in entries {
+            match self
+                .entries
+                .iter_mut()
+                .find(|e| e.fingerprint == entry.fingerprint)
+            {
+                Some(existing) => existing.count += entry.count,
+                None => self.entries.push(entry),
+            }
+        }
+        self.entries.sort_by(|a, b| {
+            (&a.path, &a.symbol, &a.fingerprint).cmp(&(&b.path, &b.symbol, &b.fingerprint))
+        });
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+        let file = BaselineFile {
+            version: BASELINE_VERSION,
+            entries: self.entries.clone(),
+            densities: self.densities.clone(),
+        };
+        let json = serde_json::to_string_pretty(&file).context("failed to serialise baseline")?;
+        fs::write(&self.path, format!("{}\n", json))
+            .with_context(|| format!("failed to write baseline {}", self.path.display()))
+    }
+
+    pub fn matcher(&self, relative_path: &Path) -> BaselineMatcher<'_> {
+        let path = normalise_path(relative_path);
+        let entries = self
+            .entries
+            .iter()
+            .filter(|e| e.path == path)
+            .collect::<Vec<&BaselineEntry>>();
+        let remaining = entries
+            .iter()
+            .map(|e| (e.fingerprint.clone(), e.count))
+            .collect();
+        BaselineMatcher {
+            relative_path: relative_path.to_path_buf(),
+            entries,
+            remaining,
+        }
+    }
+}
+
+pub struct BaselineMatcher<'a> {
+    relative_path: PathBuf,
+    entries: Vec<&'a BaselineEntry>,
+    remaining: HashMap<String, usize>,
+}
+
+impl BaselineMatcher<'_> {
+    pub fn consume(&mut self, comment: &CommentInfo) -> bool {
+        let fingerprint = fingerprint(&self.relative_path, comment);
+        match self.remaining.get_mut(&fingerprint) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn stale(&self) -> Vec<&BaselineEntry> {
+        self.entries
+            .iter()
+            .filter(|e| self.remaining.get(&e.fingerprint).is_some_and(|c| *c > 0))
+            .copied()
+            .collect()
+    }
+}
+
+pub fn fingerprint(relative_path: &Path, comment: &CommentInfo) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(normalise_path(relative_path).as_bytes());
+    hasher.update([0]);
+    hasher.update(comment.symbol().unwrap_or_default().as_bytes());
+    hasher.update([0]);
+    hasher.update(comment.normalised_text().as_bytes());
+    hasher
+        .finalize()
+        .iter()
+        .take(8)
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn normalise_path(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
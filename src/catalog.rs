@@ -0,0 +1,160 @@
+use crate::models::CommentInfo;
+
+pub struct RuleInfo {
+    code: &'static str,
+    name: &'static str,
+    summary: &'static str,
+    description: &'static str,
+    examples: &'static [&'static str],
+    suppression: &'static str,
+}
+
+impl RuleInfo {
+    pub fn code(&self) -> &'static str {
+        self.code
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn summary(&self) -> &'static str {
+        self.summary
+    }
+
+    pub fn description(&self) -> &'static str {
+        self.description
+    }
+
+    pub fn examples(&self) -> &'static [&'static str] {
+        self.examples
+    }
+
+    pub fn suppression(&self) -> &'static str {
+        self.suppression
+    }
+}
+
+pub const GENERIC_COMMENT: RuleInfo = RuleInfo {
+    code: "DC001",
+    name: "generic-comment",
+    summary: "A newly written line or block comment.",
+    description: "The code should explain itself through names and structure. A comment is \
+only kept when it records something the code cannot: a non-obvious reason, a workaround, \
+a security or performance constraint. BDD markers, tool directives and shebangs are exempt \
+under the balanced profile.",
+    examples: &[
+        "# increment the counter",
+        "// loop over the users",
+        "/* helper function */",
+    ],
+    suppression: "Remove the comment, or mark it with `decomment: allow -- <reason>`.",
+};
+
+pub const DOCSTRING: RuleInfo = RuleInfo {
+    code: "DC002",
+    name: "docstring",
+    summary: "A newly written docstring or documentation comment.",
+    description: "Docstrings that restate the signature add noise and drift out of date. Keep \
+them for public APIs and complex interfaces; otherwise make the code self-documenting. \
+Docstrings can be ignored per project or language with `include_docstrings`, \
+`languages.<name>.docstrings` or the docs-allowed profile.",
+    examples: &["\"\"\"Return the user.\"\"\"", "/** Gets the name. */"],
+    suppression: "Remove the docstring, or mark it with `decomment: allow -- <reason>`.",
+};
+
+pub const AGENT_MEMO: RuleInfo = RuleInfo {
+    code: "DC010",
+    name: "agent-memo",
+    summary: "A memo describing what was changed or how it was implemented.",
+    description: "Memo comments narrate the edit instead of describing the code. They are out \
+of date as soon as they are written and duplicate what version control already records. \
+Patterns come from the built-in list, `filters.agent_memo_patterns` and rule packs.",
+    examples: &[
+        "# Changed from X to Y",
+        "// Added new validation",
+        "// Refactored to use the cache",
+        "# Note: this implements the retry",
+    ],
+    suppression: "Remove the memo and put the explanation in the commit message.",
+};
+
+pub const CUSTOM_RULE: RuleInfo = RuleInfo {
+    code: "DC020",
+    name: "custom-rule",
+    summary: "A comment matched by a `[[rules]]` entry with a warn or block action.",
+    description: "Project rules match comments on text, type, node kind, language, path and \
+position. The finding carries the rule's own id and message, which say why the project \
+flags it.",
+    examples: &["# as requested by the reviewer"],
+    suppression: "Change the rule, or add a higher-priority `allow` rule.",
+};
+
+pub const EXPIRED_SUPPRESSION: RuleInfo = RuleInfo {
+    code: "DC030",
+    name: "expired-suppression",
+    summary: "An inline `decomment:` marker whose expiry date has passed or is invalid.",
+    description: "Suppression markers can carry an expiry (`until=YYYY-MM-DD`) so that \
+temporary exceptions are revisited. Once the date has passed the marker no longer \
+suppresses anything, and the marker itself is reported so it is removed or renewed.",
+    examples: &[
+        "# decomment: allow until=2024-01-31 -- remove after the migration",
+        "// decomment: off until=2024-06-30",
+    ],
+    suppression: "Remove the marker and the comments it covered, or move the date forward.",
+};
+
+pub const COMMENT_BUDGET: RuleInfo = RuleInfo {
+    code: "DC040",
+    name: "comment-budget",
+    summary: "New comments that exceed the configured comment budget.",
+    description: "With a `[budget]` configured, comments are allowed as long as an edit adds \
+at most `max_new_comments`, the file's ratio of comment lines to code lines stays within \
+`max_density`, and, with `ratchet`, the ratio does not rise above the one recorded by \
+`baseline update`. Once any limit is exceeded, every new comment in the edit is reported.",
+    examples: &["max_new_comments = 2, and an edit adds a third comment"],
+    suppression: "Remove comments until the edit is back within budget.",
+};
+
+pub const MISSING_LICENSE_HEADER: RuleInfo = RuleInfo {
+    code: "DC050",
+    name: "missing-license-header",
+    summary: "A newly written file without the required license header.",
+    description: "With `license.require` set, every file written from scratch must start \
+with a license header: an SPDX identifier, a copyright notice, a common license preamble or \
+a `/*! ... */` preserved comment. With `license.pattern`, a header comment must also match \
+that regular expression.",
+    examples: &["// SPDX-License-Identifier: Apache-2.0"],
+    suppression: "Add the license header at the top of the file.",
+};
+
+pub const RULES: &[RuleInfo] = &[
+    GENERIC_COMMENT,
+    DOCSTRING,
+    AGENT_MEMO,
+    CUSTOM_RULE,
+    EXPIRED_SUPPRESSION,
+    COMMENT_BUDGET,
+    MISSING_LICENSE_HEADER,
+];
+
+pub fn lookup(query: &str) -> Option<&'static RuleInfo> {
+    RULES
+        .iter()
+        .find(|rule| rule.code.eq_ignore_ascii_case(query) || rule.name == query)
+}
+
+pub fn classify(comment: &CommentInfo, is_agent_memo: bool) -> &'static RuleInfo {
+    if is_agent_memo {
+        &AGENT_MEMO
+    } else if comment.is_docstring() {
+        &DOCSTRING
+    } else {
+        &GENERIC_COMMENT
+    }
+}
+
+pub fn tag_comment(comment: &mut CommentInfo, rule: &RuleInfo) {
+    comment.set_metadata("rule_id", rule.code());
+    comment.set_metadata("rule", rule.name());
+}
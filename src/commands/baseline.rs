@@ -0,0 +1,147 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{self, Path, PathBuf};
+
+use anyhow::{Context, Result};
+use ignore::WalkBuilder;
+
+use crate::baseline::{Baseline, BaselineEntry};
+use crate::config::ConfigLoader;
+use crate::core::CommentDetector;
+use crate::policy::round_density;
+
+struct ScannedRoot {
+    baseline: Baseline,
+    entries: Vec<BaselineEntry>,
+    densities: Vec<(PathBuf, f64)>,
+}
+
+pub fn run_baseline_update(path: &Path) -> Result<u8> {
+    let scope = path::absolute(path).context("failed to resolve path")?;
+    for (root, mut scanned) in scan(&scope)? {
+        let count = scanned.entries.len();
+        scanned.baseline.replace(
+            |path| !root.join(path).starts_with(&scope),
+            scanned.entries,
+            scanned.densities,
+        );
+        scanned.baseline.save()?;
+        println!(
+            "recorded {} comments in {}",
+            count,
+            scanned.baseline.path().display()
+        );
+    }
+    Ok(0)
+}
+
+pub fn run_baseline_check(path: &Path) -> Result<u8> {
+    let scope = path::absolute(path).context("failed to resolve path")?;
+    let mut stale_count = 0;
+    for (root, scanned) in scan(&scope)? {
+        let mut current = HashMap::<&str, usize>::new();
+        for entry in &scanned.entries {
+            *current.entry(entry.fingerprint()).or_default() += 1;
+        }
+        let mut baselined = 0;
+        for entry in scanned.baseline.entries() {
+            if !root.join(entry.path()).starts_with(&scope) {
+                continue;
+            }
+            match current.get_mut(entry.fingerprint()) {
+                Some(count) if *count > 0 => {
+                    *count -= 1;
+                    baselined += 1;
+                }
+                _ => {
+                    if stale_count == 0 {
+                        println!("stale baseline entries:");
+                    }
+                    stale_count += 1;
+                    match entry.symbol() {
+                        Some(symbol) => {
+                            println!("  {} ({}): {}", entry.path(), symbol, entry.text())
+                        }
+                        None => println!("  {}: {}", entry.path(), entry.text()),
+                    }
+                }
+            }
+        }
+        let new = current.values().sum::<usize>();
+        println!(
+            "{}: {} baselined, {} not in baseline",
+            scanned.baseline.path().display(),
+            baselined,
+            new
+        );
+    }
+    if stale_count > 0 {
+        println!(
+            "\n{} stale entries; run `baseline update` to prune them",
+            stale_count
+        );
+        return Ok(1);
+    }
+    Ok(0)
+}
+
+fn scan(scope: &Path) -> Result<BTreeMap<PathBuf, ScannedRoot>> {
+    let mut roots = BTreeMap::<PathBuf, ScannedRoot>::new();
+    for entry in WalkBuilder::new(scope).build() {
+        let entry = entry.context("failed to walk directory")?;
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+        let file = entry.path();
+        let loaded = ConfigLoader::load(file)?;
+        let config = loaded.config_for(file)?;
+        if !config.baseline.enabled || loaded.is_excluded(&config, file)? {
+            continue;
+        }
+        let detector = CommentDetector::from_config(&config)?;
+        let file_name = file.to_string_lossy();
+        if detector.language_name(&file_name).is_none() {
+            continue;
+        }
+        let Ok(content) = fs::read_to_string(file) else {
+            continue;
+        };
+        if let Some(reason) = detector.detect_generated(&content, file, loaded.root()) {
+            tracing::debug!("skipping {}: {}", file.display(), reason);
+            continue;
+        }
+        let comments = match detector.detect(&content, &file_name, true) {
+            Ok(comments) => comments,
+            Err(e) => {
+                tracing::warn!("skipping {}: {:#}", file.display(), e);
+                continue;
+            }
+        };
+        let root = loaded.root().to_path_buf();
+        let scanned = match roots.get_mut(&root) {
+            Some(scanned) => scanned,
+            None => {
+                let baseline_file = root.join(&loaded.config()?.baseline.file);
+                let scanned = ScannedRoot {
+                    baseline: Baseline::load(&baseline_file)?,
+                    entries: Vec::new(),
+                    densities: Vec::new(),
+                };
+                roots.entry(root.clone()).or_insert(scanned)
+            }
+        };
+        let relative_path = loaded.relative_path(file);
+        match detector.line_stats(&content, &file_name) {
+            Ok(stats) => scanned
+                .densities
+                .push((relative_path.clone(), round_density(stats.density()))),
+            Err(e) => tracing::warn!("no line counts for {}: {:#}", file.display(), e),
+        }
+        scanned.entries.extend(
+            comments
+                .iter()
+                .map(|c| BaselineEntry::new(&relative_path, c)),
+        );
+    }
+    Ok(roots)
+}
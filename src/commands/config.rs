@@ -0,0 +1,93 @@
+use std::path::{self, Path};
+
+use anyhow::{Context, Result};
+use schemars::schema_for;
+
+use crate::config::{CONFIG_FILE_NAME, Config, ConfigLoader, LoadedConfig, load_packs, validate};
+use crate::core::CommentDetector;
+use crate::policy::{Policy, RuleEngine};
+
+pub fn run_config_check(path: &Path) -> Result<u8> {
+    let path = path::absolute(path).context("failed to resolve path")?;
+    let probe = if path.is_dir() {
+        path.join(CONFIG_FILE_NAME)
+    } else {
+        path.clone()
+    };
+    let loaded = ConfigLoader::load(&probe)?;
+    println!("path: {}", path.display());
+    println!("project root: {}", loaded.root().display());
+    if loaded.sources().is_empty() {
+        println!("sources: none (using defaults)");
+    } else {
+        println!("sources:");
+        for source in loaded.sources() {
+            println!("  {}", source.display());
+        }
+    }
+    let problems = validate(&loaded);
+    if let Ok(config) = loaded.config_for(&path) {
+        print_resolved(&loaded, &config, &path)?;
+    }
+    if problems.is_empty() {
+        println!("\nconfiguration is valid");
+        return Ok(0);
+    }
+    println!("\nproblems:");
+    for problem in &problems {
+        println!("  - {}", problem);
+    }
+    Ok(1)
+}
+
+pub fn run_config_schema() -> Result<u8> {
+    let schema = schema_for!(Config);
+    let json = serde_json::to_string_pretty(&schema).context("failed to serialise schema")?;
+    println!("{}", json);
+    Ok(0)
+}
+
+fn print_resolved(loaded: &LoadedConfig, config: &Config, path: &Path) -> Result<()> {
+    let excluded = loaded.is_excluded(config, path)?;
+    let detector = CommentDetector::from_config(config)?;
+    let language = detector.language_name(&path.to_string_lossy());
+    let policy = Policy::new(config, config.profile);
+    println!("relative path: {}", loaded.relative_path(path).display());
+    println!("excluded: {}", if excluded { "yes" } else { "no" });
+    println!("language: {}", language.unwrap_or("unsupported"));
+    println!("profile: {}", policy.profile());
+    println!(
+        "docstrings: {}",
+        if policy.include_docstrings(language) {
+            "checked"
+        } else {
+            "ignored"
+        }
+    );
+    if let Ok(packs) = load_packs(loaded.root(), &config.packs) {
+        if packs.is_empty() {
+            println!("rule packs: none");
+        } else {
+            println!("rule packs:");
+        }
+        for pack in &packs {
+            match pack.description() {
+                Some(description) => println!("  {} - {}", pack.id(), description),
+                None => println!("  {}", pack.id()),
+            }
+        }
+    }
+    if let Ok(engine) = RuleEngine::new(&config.rules, loaded.root()) {
+        if engine.rules().is_empty() {
+            println!("rules: none");
+        } else {
+            println!("rules (in precedence order):");
+        }
+        for rule in engine.rules() {
+            println!("  {} -> {}", rule.id(), rule.action().as_str());
+        }
+    }
+    let resolved = toml::to_string_pretty(config).context("failed to serialise configuration")?;
+    println!("\nresolved configuration:\n{}", resolved.trim_end());
+    Ok(())
+}
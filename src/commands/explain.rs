@@ -0,0 +1,46 @@
+use anyhow::Result;
+
+use crate::catalog::{RULES, RuleInfo, lookup};
+
+pub fn run_explain(rule: Option<&str>) -> Result<u8> {
+    let Some(query) = rule else {
+        print_rule_list();
+        return Ok(0);
+    };
+    match lookup(query) {
+        Some(rule) => {
+            print_rule(rule);
+            Ok(0)
+        }
+        None => {
+            eprintln!("unknown rule: {}\n", query);
+            print_rule_list();
+            Ok(1)
+        }
+    }
+}
+
+fn print_rule_list() {
+    let width = RULES.iter().map(|r| r.name().len()).max().unwrap_or(0);
+    println!("rules:");
+    for rule in RULES {
+        println!(
+            "  {} {:<width$} {}",
+            rule.code(),
+            rule.name(),
+            rule.summary(),
+            width = width
+        );
+    }
+}
+
+fn print_rule(rule: &RuleInfo) {
+    println!("{} {}", rule.code(), rule.name());
+    println!("{}\n", rule.summary());
+    println!("{}\n", rule.description());
+    println!("examples:");
+    for example in rule.examples() {
+        println!("  {}", example);
+    }
+    println!("\nto resolve: {}", rule.suppression());
+}
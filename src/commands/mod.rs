@@ -0,0 +1,7 @@
+mod baseline;
+mod config;
+mod explain;
+
+pub use baseline::{run_baseline_check, run_baseline_update};
+pub use config::{run_config_check, run_config_schema};
+pub use explain::run_explain;
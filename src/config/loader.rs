@@ -0,0 +1,217 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use toml::{Table, Value};
+
+use crate::config::paths::{PathMatcher, rebase_pattern};
+use crate::config::{CONFIG_FILE_NAME, Config};
+
+const ACCUMULATED_KEYS: &[&str] = &["exclude", "overrides", "rules"];
+
+pub struct ConfigLoader;
+
+impl ConfigLoader {
+    pub fn load(file_path: &Path) -> Result<LoadedConfig> {
+        let start = file_path.parent().unwrap_or(Path::new("."));
+        let mut project_files = Vec::new();
+        for dir in start.ancestors() {
+            let candidate = dir.join(CONFIG_FILE_NAME);
+            if !candidate.is_file() {
+                continue;
+            }
+            let table = read_table(&candidate)?;
+            let is_root = table.get("root").and_then(|v| v.as_bool()).unwrap_or(false);
+            project_files.push((dir.to_path_buf(), candidate, table));
+            if is_root {
+                break;
+            }
+        }
+        let root = match project_files.last() {
+            Some((dir, _, _)) => dir.clone(),
+            None => find_repository_root(start).unwrap_or_else(|| start.to_path_buf()),
+        };
+        let mut table = Table::new();
+        let mut sources = Vec::new();
+        if let Some(user_file) = user_config_path()
+            && user_file.is_file()
+        {
+            merge_config_tables(&mut table, read_table(&user_file)?);
+            sources.push(user_file);
+        }
+        for (dir, path, mut project_table) in project_files.into_iter().rev() {
+            let prefix = dir
+                .strip_prefix(&root)
+                .unwrap_or(Path::new(""))
+                .to_string_lossy()
+                .replace('\\', "/");
+            rebase_path_patterns(&mut project_table, &prefix);
+            merge_config_tables(&mut table, project_table);
+            sources.push(path);
+        }
+        Ok(LoadedConfig {
+            root,
+            sources,
+            table,
+        })
+    }
+}
+
+pub struct LoadedConfig {
+    root: PathBuf,
+    sources: Vec<PathBuf>,
+    table: Table,
+}
+
+impl LoadedConfig {
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn sources(&self) -> &[PathBuf] {
+        &self.sources
+    }
+
+    pub fn relative_path(&self, path: &Path) -> PathBuf {
+        path.strip_prefix(&self.root).unwrap_or(path).to_path_buf()
+    }
+
+    pub fn config(&self) -> Result<Config> {
+        deserialize_config(self.table.clone())
+    }
+
+    pub fn config_for(&self, path: &Path) -> Result<Config> {
+        let base = self.config()?;
+        if base.overrides.is_empty() {
+            return Ok(base);
+        }
+        let relative_path = self.relative_path(path);
+        let mut table = self.table.clone();
+        table.remove("overrides");
+        for path_override in &base.overrides {
+            let matcher = PathMatcher::new(&self.root, &path_override.paths)?;
+            if matcher.is_match(&relative_path) {
+                tracing::debug!(
+                    "applying override for {:?} to {}",
+                    path_override.paths,
+                    relative_path.display()
+                );
+                merge_tables(&mut table, path_override.settings.clone());
+            }
+        }
+        deserialize_config(table)
+    }
+
+    pub fn override_configs(&self) -> Result<Vec<(Vec<String>, Result<Config>)>> {
+        let base = self.config()?;
+        let mut table = self.table.clone();
+        table.remove("overrides");
+        Ok(base
+            .overrides
+            .into_iter()
+            .map(|path_override| {
+                let mut override_table = table.clone();
+                merge_tables(&mut override_table, path_override.settings);
+                (path_override.paths, deserialize_config(override_table))
+            })
+            .collect())
+    }
+
+    pub fn is_excluded(&self, config: &Config, path: &Path) -> Result<bool> {
+        if config.exclude.is_empty() {
+            return Ok(false);
+        }
+        let matcher = PathMatcher::new(&self.root, &config.exclude)?;
+        Ok(matcher.is_match(&self.relative_path(path)))
+    }
+}
+
+fn deserialize_config(table: Table) -> Result<Config> {
+    Value::Table(table)
+        .try_into()
+        .context("invalid configuration")
+}
+
+fn find_repository_root(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .map(Path::to_path_buf)
+}
+
+fn user_config_path() -> Option<PathBuf> {
+    let base = match env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(env::var_os("HOME")?).join(".config"),
+    };
+    Some(base.join("decomment").join("config.toml"))
+}
+
+fn read_table(path: &Path) -> Result<Table> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+    content
+        .parse::<Table>()
+        .with_context(|| format!("failed to parse {}", path.display()))
+}
+
+fn rebase_path_patterns(table: &mut Table, prefix: &str) {
+    if prefix.is_empty() {
+        return;
+    }
+    let rebase_all = |patterns: &mut Value| {
+        if let Value::Array(patterns) = patterns {
+            for pattern in patterns.iter_mut() {
+                if let Value::String(p) = pattern {
+                    *p = rebase_pattern(p, prefix);
+                }
+            }
+        }
+    };
+    if let Some(exclude) = table.get_mut("exclude") {
+        rebase_all(exclude);
+    }
+    for key in ["overrides", "rules"] {
+        if let Some(Value::Array(entries)) = table.get_mut(key) {
+            for entry in entries.iter_mut() {
+                if let Value::Table(entry) = entry
+                    && let Some(paths) = entry.get_mut("paths")
+                {
+                    rebase_all(paths);
+                }
+            }
+        }
+    }
+}
+
+fn merge_config_tables(base: &mut Table, overlay: Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Array(existing)), Value::Array(incoming))
+                if ACCUMULATED_KEYS.contains(&key.as_str()) =>
+            {
+                existing.extend(incoming);
+            }
+            (Some(Value::Table(existing)), Value::Table(incoming)) => {
+                merge_tables(existing, incoming);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn merge_tables(base: &mut Table, overlay: Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(existing)), Value::Table(incoming)) => {
+                merge_tables(existing, incoming);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
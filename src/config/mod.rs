@@ -0,0 +1,319 @@
+mod loader;
+mod packs;
+mod paths;
+mod validate;
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use toml::Table;
+
+use crate::error::FailMode;
+use crate::models::{CommentPosition, CommentType, Severity};
+use crate::policy::Profile;
+
+pub use loader::{ConfigLoader, LoadedConfig};
+pub use packs::{RulePack, load_packs};
+pub use paths::PathMatcher;
+pub use validate::validate;
+
+pub const CONFIG_FILE_NAME: &str = ".decomment.toml";
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub root: bool,
+    pub profile: Profile,
+    pub on_error: FailMode,
+    pub include_docstrings: bool,
+    pub filters: FilterConfig,
+    pub languages: HashMap<String, LanguageConfig>,
+    pub message: MessageConfig,
+    pub severity: SeverityConfig,
+    pub shadow: ShadowConfig,
+    pub generated: GeneratedConfig,
+    pub packs: PacksConfig,
+    pub baseline: BaselineConfig,
+    pub budget: BudgetConfig,
+    pub license: LicenseConfig,
+    pub rules: Vec<RuleConfig>,
+    pub exclude: Vec<String>,
+    pub overrides: Vec<PathOverride>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            root: false,
+            profile: Profile::default(),
+            on_error: FailMode::default(),
+            include_docstrings: true,
+            filters: FilterConfig::default(),
+            languages: HashMap::new(),
+            message: MessageConfig::default(),
+            severity: SeverityConfig::default(),
+            shadow: ShadowConfig::default(),
+            generated: GeneratedConfig::default(),
+            packs: PacksConfig::default(),
+            baseline: BaselineConfig::default(),
+            budget: BudgetConfig::default(),
+            license: LicenseConfig::default(),
+            rules: Vec::new(),
+            exclude: Vec::new(),
+            overrides: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct FilterConfig {
+    pub bdd: bool,
+    pub directive: bool,
+    pub shebang: bool,
+    pub order: Vec<FilterName>,
+    pub bdd_keywords: Vec<String>,
+    pub directive_prefixes: Vec<String>,
+    pub agent_memo_patterns: Vec<String>,
+    pub allow_patterns: Vec<String>,
+}
+
+impl Default for FilterConfig {
+    fn default() -> Self {
+        Self {
+            bdd: true,
+            directive: true,
+            shebang: true,
+            order: Vec::new(),
+            bdd_keywords: Vec::new(),
+            directive_prefixes: Vec::new(),
+            agent_memo_patterns: Vec::new(),
+            allow_patterns: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum FilterName {
+    Allow,
+    Bdd,
+    Directive,
+    Shebang,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct LanguageConfig {
+    pub enabled: bool,
+    pub extensions: Vec<String>,
+    pub docstrings: Option<bool>,
+    pub block: Option<Vec<CommentType>>,
+    pub filters: Option<Vec<FilterName>>,
+    pub directives: Vec<String>,
+    pub public_docs: Option<DocPolicy>,
+    pub private_docs: Option<DocPolicy>,
+    pub module_docs: Option<DocPolicy>,
+}
+
+impl Default for LanguageConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            extensions: Vec::new(),
+            docstrings: None,
+            block: None,
+            filters: None,
+            directives: Vec::new(),
+            public_docs: None,
+            private_docs: None,
+            module_docs: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct MessageConfig {
+    pub prompt: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct SeverityConfig {
+    pub comment: Severity,
+    pub docstring: Severity,
+    pub agent_memo: Severity,
+}
+
+impl Default for SeverityConfig {
+    fn default() -> Self {
+        Self {
+            comment: Severity::Block,
+            docstring: Severity::Block,
+            agent_memo: Severity::Block,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct ShadowConfig {
+    pub enabled: bool,
+    pub log: PathBuf,
+}
+
+impl Default for ShadowConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            log: PathBuf::from(".decomment/shadow.jsonl"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct GeneratedConfig {
+    pub enabled: bool,
+    pub markers: Vec<String>,
+    pub vendor_directories: Vec<String>,
+}
+
+impl Default for GeneratedConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            markers: Vec::new(),
+            vendor_directories: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct PacksConfig {
+    pub directories: Vec<PathBuf>,
+    pub files: Vec<PathBuf>,
+    pub disabled: Vec<String>,
+}
+
+impl Default for PacksConfig {
+    fn default() -> Self {
+        Self {
+            directories: vec![PathBuf::from(".decomment/packs")],
+            files: Vec::new(),
+            disabled: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct BaselineConfig {
+    pub enabled: bool,
+    pub file: PathBuf,
+}
+
+impl Default for BaselineConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            file: PathBuf::from(".decomment/baseline.json"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct BudgetConfig {
+    pub max_new_comments: Option<usize>,
+    pub max_density: Option<f64>,
+    pub ratchet: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct LicenseConfig {
+    pub allow: bool,
+    pub require: bool,
+    pub pattern: Option<String>,
+}
+
+impl Default for LicenseConfig {
+    fn default() -> Self {
+        Self {
+            allow: true,
+            require: false,
+            pattern: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DocPolicy {
+    Allow,
+    Docstring,
+    Comment,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleAction {
+    Allow,
+    Warn,
+    Block,
+}
+
+impl RuleAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RuleAction::Allow => "allow",
+            RuleAction::Warn => "warn",
+            RuleAction::Block => "block",
+        }
+    }
+
+    pub fn severity(&self) -> Option<Severity> {
+        match self {
+            RuleAction::Allow => None,
+            RuleAction::Warn => Some(Severity::Warn),
+            RuleAction::Block => Some(Severity::Block),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RuleConfig {
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub pattern: Option<String>,
+    #[serde(default)]
+    pub comment_types: Vec<CommentType>,
+    #[serde(default)]
+    pub node_kinds: Vec<String>,
+    #[serde(default)]
+    pub languages: Vec<String>,
+    #[serde(default)]
+    pub paths: Vec<String>,
+    #[serde(default)]
+    pub positions: Vec<CommentPosition>,
+    pub action: RuleAction,
+    #[serde(default)]
+    pub message: Option<String>,
+    #[serde(default)]
+    pub priority: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PathOverride {
+    pub paths: Vec<String>,
+    #[serde(flatten)]
+    #[schemars(with = "serde_json::Map<String, serde_json::Value>")]
+    pub settings: Table,
+}
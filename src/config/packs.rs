@@ -0,0 +1,178 @@
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::config::PacksConfig;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RulePackFile {
+    namespace: String,
+    name: String,
+    version: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    directive_prefixes: Vec<String>,
+    #[serde(default)]
+    agent_memo_patterns: Vec<String>,
+    #[serde(default)]
+    bdd_keywords: Vec<String>,
+    #[serde(default)]
+    allow_patterns: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackId {
+    namespace: String,
+    name: String,
+    version: String,
+}
+
+impl PackId {
+    pub fn qualified_name(&self) -> String {
+        format!("{}/{}", self.namespace, self.name)
+    }
+}
+
+impl fmt::Display for PackId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}@{}", self.namespace, self.name, self.version)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RulePack {
+    id: PackId,
+    path: PathBuf,
+    description: Option<String>,
+    directive_prefixes: Vec<String>,
+    agent_memo_patterns: Vec<Regex>,
+    bdd_keywords: Vec<String>,
+    allow_patterns: Vec<Regex>,
+}
+
+impl RulePack {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("failed to read rule pack {}", path.display()))?;
+        let file = toml::from_str::<RulePackFile>(&content)
+            .with_context(|| format!("failed to parse rule pack {}", path.display()))?;
+        for (field, value) in [
+            ("namespace", &file.namespace),
+            ("name", &file.name),
+            ("version", &file.version),
+        ] {
+            if !is_valid_identifier(value) {
+                bail!(
+                    "rule pack {} has an invalid {}: {:?}",
+                    path.display(),
+                    field,
+                    value
+                );
+            }
+        }
+        let compile = |patterns: &[String]| {
+            patterns
+                .iter()
+                .map(|p| {
+                    Regex::new(p).with_context(|| {
+                        format!("invalid pattern in rule pack {}: {}", path.display(), p)
+                    })
+                })
+                .collect::<Result<Vec<Regex>>>()
+        };
+        Ok(Self {
+            id: PackId {
+                namespace: file.namespace,
+                name: file.name,
+                version: file.version,
+            },
+            path: path.to_path_buf(),
+            description: file.description,
+            agent_memo_patterns: compile(&file.agent_memo_patterns)?,
+            allow_patterns: compile(&file.allow_patterns)?,
+            directive_prefixes: file.directive_prefixes,
+            bdd_keywords: file.bdd_keywords,
+        })
+    }
+
+    pub fn id(&self) -> &PackId {
+        &self.id
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    pub fn directive_prefixes(&self) -> &[String] {
+        &self.directive_prefixes
+    }
+
+    pub fn agent_memo_patterns(&self) -> &[Regex] {
+        &self.agent_memo_patterns
+    }
+
+    pub fn bdd_keywords(&self) -> &[String] {
+        &self.bdd_keywords
+    }
+
+    pub fn allow_patterns(&self) -> &[Regex] {
+        &self.allow_patterns
+    }
+}
+
+pub fn load_packs(root: &Path, config: &PacksConfig) -> Result<Vec<RulePack>> {
+    let mut paths = Vec::new();
+    for directory in &config.directories {
+        let directory = root.join(directory);
+        if !directory.is_dir() {
+            continue;
+        }
+        let mut entries = fs::read_dir(&directory)
+            .with_context(|| format!("failed to read pack directory {}", directory.display()))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+            .collect::<Vec<PathBuf>>();
+        entries.sort();
+        paths.extend(entries);
+    }
+    paths.extend(config.files.iter().map(|file| root.join(file)));
+    let mut packs = Vec::<RulePack>::new();
+    for path in paths {
+        let pack = RulePack::load(&path)?;
+        if config.disabled.contains(&pack.id().qualified_name()) {
+            tracing::debug!("rule pack {} is disabled", pack.id());
+            continue;
+        }
+        if let Some(existing) = packs
+            .iter()
+            .find(|p| p.id().qualified_name() == pack.id().qualified_name())
+        {
+            bail!(
+                "rule pack {} is loaded twice ({} and {})",
+                pack.id().qualified_name(),
+                existing.path().display(),
+                pack.path().display()
+            );
+        }
+        tracing::debug!("loaded rule pack {} from {}", pack.id(), path.display());
+        packs.push(pack);
+    }
+    Ok(packs)
+}
+
+fn is_valid_identifier(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '+'))
+}
@@ -0,0 +1,44 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+
+pub struct PathMatcher {
+    matcher: Gitignore,
+}
+
+impl PathMatcher {
+    pub fn new(root: &Path, patterns: &[String]) -> Result<Self> {
+        let mut builder = GitignoreBuilder::new(root);
+        for pattern in patterns {
+            builder
+                .add_line(None, pattern)
+                .with_context(|| format!("invalid path pattern: {}", pattern))?;
+        }
+        let matcher = builder.build().context("failed to build path matcher")?;
+        Ok(Self { matcher })
+    }
+
+    pub fn is_match(&self, relative_path: &Path) -> bool {
+        self.matcher
+            .matched_path_or_any_parents(relative_path, false)
+            .is_ignore()
+    }
+}
+
+pub fn rebase_pattern(pattern: &str, prefix: &str) -> String {
+    if prefix.is_empty() {
+        return pattern.to_owned();
+    }
+    let (negation, body) = match pattern.strip_prefix('!') {
+        Some(rest) => ("!", rest),
+        None => ("", pattern),
+    };
+    let anchored = body.starts_with('/') || body.trim_end_matches('/').contains('/');
+    let body = body.trim_start_matches('/');
+    if anchored {
+        format!("{}/{}/{}", negation, prefix, body)
+    } else {
+        format!("{}/{}/**/{}", negation, prefix, body)
+    }
+}
@@ -0,0 +1,83 @@
+use std::path::Path;
+
+use crate::config::{Config, LoadedConfig, PathMatcher, load_packs};
+use crate::core::{GeneratedFileDetector, LanguageRegistry};
+use crate::filters::{AgentMemoFilter, AllowFilter};
+use crate::policy::{LicenseCheck, RuleEngine};
+
+pub fn validate(loaded: &LoadedConfig) -> Vec<String> {
+    let mut problems = Vec::new();
+    let config = match loaded.config() {
+        Ok(config) => config,
+        Err(e) => {
+            problems.push(format!("{:#}", e));
+            return problems;
+        }
+    };
+    validate_config(&config, loaded.root(), "", &mut problems);
+    match loaded.override_configs() {
+        Ok(overrides) => {
+            for (paths, result) in overrides {
+                let scope = format!("overrides {:?}: ", paths);
+                if let Err(e) = PathMatcher::new(loaded.root(), &paths) {
+                    problems.push(format!("{}{:#}", scope, e));
+                }
+                match result {
+                    Ok(override_config) => {
+                        validate_config(&override_config, loaded.root(), &scope, &mut problems)
+                    }
+                    Err(e) => problems.push(format!("{}{:#}", scope, e)),
+                }
+            }
+        }
+        Err(e) => problems.push(format!("{:#}", e)),
+    }
+    problems.dedup();
+    problems
+}
+
+fn validate_config(config: &Config, root: &Path, scope: &str, problems: &mut Vec<String>) {
+    let registry = LanguageRegistry::new();
+    let mut languages = config.languages.iter().collect::<Vec<_>>();
+    languages.sort_by_key(|(name, _)| name.as_str());
+    for (name, language) in languages {
+        if !registry.is_known_language(name) {
+            problems.push(format!("{}unknown language: {}", scope, name));
+        }
+        for ext in &language.extensions {
+            if ext.trim_start_matches('.').is_empty() {
+                problems.push(format!("{}empty extension for language {}", scope, name));
+            }
+        }
+    }
+    if let Err(e) = AgentMemoFilter::with_patterns(&config.filters.agent_memo_patterns) {
+        problems.push(format!("{}{:#}", scope, e));
+    }
+    if let Err(e) = GeneratedFileDetector::from_config(&config.generated) {
+        problems.push(format!("{}{:#}", scope, e));
+    }
+    match load_packs(root, &config.packs) {
+        Ok(packs) => {
+            if let Err(e) = AllowFilter::with_patterns(&config.filters.allow_patterns, &packs) {
+                problems.push(format!("{}{:#}", scope, e));
+            }
+        }
+        Err(e) => problems.push(format!("{}{:#}", scope, e)),
+    }
+    if let Err(e) = PathMatcher::new(root, &config.exclude) {
+        problems.push(format!("{}exclude: {:#}", scope, e));
+    }
+    if let Err(e) = LicenseCheck::new(&config.license) {
+        problems.push(format!("{}{:#}", scope, e));
+    }
+    if let Err(e) = RuleEngine::new(&config.rules, root) {
+        problems.push(format!("{}{:#}", scope, e));
+    }
+    for rule in &config.rules {
+        for language in &rule.languages {
+            if !registry.is_known_language(language) {
+                problems.push(format!("{}unknown language in rule: {}", scope, language));
+            }
+        }
+    }
+}
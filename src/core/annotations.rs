@@ -0,0 +1,62 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+static TS_CHECK_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^\s*//\s*@ts-check\b").unwrap());
+
+static JSDOC_TYPE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"@(?:type|param|arg|argument|returns?|typedef|callback|template|property|prop|satisfies|implements|extends|augments|enum|this|overload|import)\b",
+    )
+    .unwrap()
+});
+
+static LUALS_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"^---\s*@\w+").unwrap());
+
+static TYPE_COMMENT_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"^#\s*type:\s*(\S+)").unwrap());
+
+static PHPDOC_ANNOTATION_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"@[A-Z]\w*(?:\\\w+)*\s*\(|@[A-Z]\w*(?:\\\w+)+").unwrap());
+
+static SORBET_SIGIL_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^#\s*typed:\s*(?:ignore|false|true|strict|strong)\b").unwrap());
+
+pub struct Annotations {
+    language: String,
+    ts_check: bool,
+}
+
+impl Annotations {
+    pub fn for_file(language: &str, content: &str) -> Self {
+        Self {
+            language: language.to_owned(),
+            ts_check: language == "javascript" && TS_CHECK_PATTERN.is_match(content),
+        }
+    }
+
+    pub fn grammar(&self, text: &str) -> Option<&'static str> {
+        let text = text.trim();
+        match self.language.as_str() {
+            "javascript"
+                if self.ts_check
+                    && text.starts_with("/**")
+                    && JSDOC_TYPE_PATTERN.is_match(text) =>
+            {
+                Some("jsdoc")
+            }
+            "lua" if LUALS_PATTERN.is_match(text) => Some("luals"),
+            "python" if is_type_comment(text) => Some("type-comment"),
+            "php" if text.starts_with("/**") && PHPDOC_ANNOTATION_PATTERN.is_match(text) => {
+                Some("phpdoc")
+            }
+            "ruby" if SORBET_SIGIL_PATTERN.is_match(text) => Some("sorbet"),
+            _ => None,
+        }
+    }
+}
+
+fn is_type_comment(text: &str) -> bool {
+    TYPE_COMMENT_PATTERN
+        .captures(text)
+        .is_some_and(|c| !c[1].starts_with("ignore"))
+}
@@ -0,0 +1,129 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::core::directive_registry::DirectiveRegistry;
+use crate::models::{CommentPosition, CommentType};
+
+static LICENSE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(concat!(
+        r"(?i)spdx-license-identifier:|spdx-filecopyrighttext:|\bcopyright\b|\(c\)\s*\d{4}|©|",
+        r"all rights reserved|licensed under|permission is hereby granted|",
+        r"this (?:program|file|library) is free software|",
+        r"use of this source code is governed by|@license\b|@preserve\b|",
+        r"\b(?:apache|mit|bsd|isc|mozilla public|gnu (?:lesser |affero )?general public) license\b"
+    ))
+    .unwrap()
+});
+
+static TODO_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(?:TODO|FIXME|HACK|XXX|BUG)\b").unwrap());
+
+static BANNER_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[=\-*#~_+/<>]{4,}").unwrap());
+
+static CODE_PATTERNS: Lazy<Vec<Regex>> = Lazy::new(|| {
+    vec![
+        Regex::new(r"[;{}]$").unwrap(),
+        Regex::new(r"^(?:if|for|while|return|let|const|var|def|fn|func|import|from|class|pub|use|#include)\b.*[(){};=:]").unwrap(),
+        Regex::new(r"^[\w.\[\]]+\s*(?:=|\+=|-=|==|:=)\s*\S").unwrap(),
+        Regex::new(r"^[\w.]+\(.*\)$").unwrap(),
+    ]
+});
+
+const DELIMITERS: &[&str] = &[
+    "///", "//!", "//", "/**", "/*!", "/*", "*/", "#", "--", "<!--", "-->", "*",
+];
+
+pub fn classify_comment_type(
+    text: &str,
+    node_kind: &str,
+    language: &str,
+    directives: &DirectiveRegistry,
+    line_number: usize,
+    position: CommentPosition,
+) -> CommentType {
+    let stripped = text.trim();
+    if line_number == 1 && stripped.starts_with("#!") {
+        return CommentType::Shebang;
+    }
+    if directives.is_directive(Some(language), stripped) {
+        return CommentType::Directive;
+    }
+    if position == CommentPosition::FileHeader
+        && (LICENSE_PATTERN.is_match(stripped)
+            || (stripped.starts_with("/*!") && language != "rust"))
+    {
+        return CommentType::LicenseHeader;
+    }
+    let body = comment_body(stripped);
+    if BANNER_PATTERN.is_match(&body) {
+        return CommentType::Banner;
+    }
+    if stripped.starts_with("\"\"\"") || stripped.starts_with("'''") {
+        return CommentType::Docstring;
+    }
+    if (stripped.starts_with("///") && !stripped.starts_with("////"))
+        || (stripped.starts_with("/**") && !stripped.starts_with("/**/"))
+    {
+        return CommentType::OuterDoc;
+    }
+    if stripped.starts_with("//!") || stripped.starts_with("/*!") {
+        return CommentType::InnerDoc;
+    }
+    if TODO_PATTERN.is_match(&body) {
+        return CommentType::Todo;
+    }
+    if is_commented_out_code(stripped) {
+        return CommentType::CommentedOutCode;
+    }
+    delimiter_type(stripped, node_kind)
+}
+
+fn delimiter_type(stripped: &str, node_kind: &str) -> CommentType {
+    if node_kind == "line_comment" {
+        return CommentType::Line;
+    }
+    if node_kind == "block_comment" {
+        return CommentType::Block;
+    }
+    if stripped.starts_with("//") || stripped.starts_with('#') {
+        return CommentType::Line;
+    }
+    if stripped.starts_with("/*") || stripped.starts_with("<!--") || stripped.starts_with("--") {
+        return CommentType::Block;
+    }
+    CommentType::Line
+}
+
+fn strip_delimiters(line: &str) -> &str {
+    let mut line = line.trim();
+    if let Some(prefix) = DELIMITERS.iter().find(|d| line.starts_with(*d)) {
+        line = line[prefix.len()..].trim_start();
+    }
+    for suffix in ["*/", "-->"] {
+        if let Some(rest) = line.strip_suffix(suffix) {
+            line = rest.trim_end();
+        }
+    }
+    line
+}
+
+fn comment_body(stripped: &str) -> String {
+    stripped
+        .lines()
+        .map(strip_delimiters)
+        .find(|l| !l.is_empty())
+        .unwrap_or_else(|| stripped.trim_start_matches(|c| "/#*-".contains(c)))
+        .to_owned()
+}
+
+fn is_commented_out_code(stripped: &str) -> bool {
+    let lines = stripped
+        .lines()
+        .map(strip_delimiters)
+        .filter(|l| !l.is_empty())
+        .collect::<Vec<&str>>();
+    !lines.is_empty()
+        && lines
+            .iter()
+            .all(|line| CODE_PATTERNS.iter().any(|p| p.is_match(line)))
+}
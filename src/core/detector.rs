@@ -1,12 +1,22 @@
+use std::collections::HashSet;
+use std::ops::RangeInclusive;
 use std::path::Path;
 
+use anyhow::{Context, Result, anyhow};
 use regex::Regex;
 use streaming_iterator::StreamingIterator;
-use tree_sitter::{Language, Parser, Query, QueryCursor};
+use tree_sitter::{Language, Node, Parser, Query, QueryCursor, Tree};
 
+use crate::config::Config;
+use crate::core::annotations::Annotations;
+use crate::core::classifier::classify_comment_type;
+use crate::core::directive_registry::DirectiveRegistry;
+use crate::core::generated::{GeneratedFileDetector, GeneratedReason};
 use crate::core::language_registry::{LanguageRegistry, get_language};
 use crate::core::query_templates::{DOCSTRING_QUERIES, QUERY_TEMPLATES};
-use crate::models::{CommentInfo, CommentType};
+use crate::core::{golang, python, rust};
+use crate::error::{Stage, StageContext};
+use crate::models::{CommentInfo, CommentPosition, CommentType, LineStats};
 
 fn get_docstring_pattern(lang_name: &str) -> Option<&'static str> {
     match lang_name {
@@ -17,21 +27,37 @@ fn get_docstring_pattern(lang_name: &str) -> Option<&'static str> {
 
 pub struct CommentDetector {
     registry: LanguageRegistry,
+    directives: DirectiveRegistry,
+    generated: GeneratedFileDetector,
 }
 
 impl CommentDetector {
     pub fn new() -> Self {
         Self {
             registry: LanguageRegistry::new(),
+            directives: DirectiveRegistry::new(),
+            generated: GeneratedFileDetector::new(),
         }
     }
 
-    pub fn detect(
+    pub fn from_config(config: &Config) -> Result<Self> {
+        Ok(Self {
+            registry: LanguageRegistry::from_config(config),
+            directives: DirectiveRegistry::from_config(config),
+            generated: GeneratedFileDetector::from_config(&config.generated)?,
+        })
+    }
+
+    pub fn detect_generated(
         &self,
         content: &str,
-        file_path: &str,
-        include_docstrings: bool,
-    ) -> Vec<CommentInfo> {
+        file_path: &Path,
+        root: &Path,
+    ) -> Option<GeneratedReason> {
+        self.generated.detect(content, file_path, root)
+    }
+
+    pub fn language_name(&self, file_path: &str) -> Option<&'static str> {
         let ext = Path::new(file_path)
             .extension()
             .and_then(|e| e.to_str())
@@ -42,30 +68,72 @@ impl CommentDetector {
                     .unwrap_or_default()
             })
             .to_lowercase();
-        let lang_name = match self.registry.get_language_name(&ext) {
-            Some(name) => name,
-            None => return Vec::new(),
-        };
-        let lang = match get_language(lang_name) {
-            Some(l) => l,
-            None => return Vec::new(),
-        };
-        let mut parser = Parser::new();
-        if parser.set_language(&lang).is_err() {
-            return Vec::new();
-        }
-        let tree = match parser.parse(content, None) {
-            Some(t) => t,
-            None => return Vec::new(),
+        self.registry.get_language_name(&ext)
+    }
+
+    pub fn detect(
+        &self,
+        content: &str,
+        file_path: &str,
+        include_docstrings: bool,
+    ) -> Result<Vec<CommentInfo>> {
+        let parsed = self.parse(content, file_path)?;
+        let context = FileContext::new(content, parsed.2.clone(), 0);
+        let annotations = Annotations::for_file(parsed.0, content);
+        self.collect(
+            content,
+            file_path,
+            include_docstrings,
+            &parsed,
+            &annotations,
+            Some(&context),
+        )
+    }
+
+    pub fn detect_fragment(
+        &self,
+        fragment: &str,
+        file_content: Option<&str>,
+        file_path: &str,
+        include_docstrings: bool,
+    ) -> Result<Vec<CommentInfo>> {
+        let parsed = self.parse(fragment, file_path)?;
+        let context = match file_content.and_then(|f| f.find(fragment).map(|o| (f, o))) {
+            Some((file, offset)) => {
+                let (_, _, file_tree) = self.parse(file, file_path)?;
+                Some(FileContext::new(file, file_tree, offset))
+            }
+            None => None,
         };
+        let annotations = Annotations::for_file(parsed.0, file_content.unwrap_or(fragment));
+        self.collect(
+            fragment,
+            file_path,
+            include_docstrings,
+            &parsed,
+            &annotations,
+            context.as_ref(),
+        )
+    }
+
+    fn collect(
+        &self,
+        content: &str,
+        file_path: &str,
+        include_docstrings: bool,
+        parsed: &(&'static str, Language, Tree),
+        annotations: &Annotations,
+        context: Option<&FileContext>,
+    ) -> Result<Vec<CommentInfo>> {
+        let (lang_name, lang, tree) = parsed;
+        let lang_name = *lang_name;
         let query_pattern = QUERY_TEMPLATES
             .get(lang_name)
             .copied()
             .unwrap_or("(comment) @comment");
-        let query = match Query::new(&lang, query_pattern) {
-            Ok(q) => q,
-            Err(_) => return Vec::new(),
-        };
+        let query = Query::new(lang, query_pattern)
+            .with_context(|| format!("invalid {} comment query", lang_name))
+            .stage(Stage::Query)?;
         let mut cursor = QueryCursor::new();
         let source_bytes = content.as_bytes();
         let mut comments = Vec::new();
@@ -84,54 +152,177 @@ impl CommentDetector {
                 }
                 let text = text.to_owned();
                 let line_number = node.start_position().row + 1;
-                let comment_type = self.determine_comment_type(&text, node.kind());
-                let is_docstring = comment_type == CommentType::Docstring;
+                let (file_node, file_bytes, header_end) = locate(context, node, source_bytes);
+                let position = comment_position(file_node, file_bytes, header_end);
+                let comment_type = classify_comment_type(
+                    &text,
+                    node.kind(),
+                    lang_name,
+                    &self.directives,
+                    line_number,
+                    position,
+                );
+                let comment_type = match lang_name {
+                    "golang" => {
+                        golang::refine_comment_type(file_node, &text, file_bytes, comment_type)
+                    }
+                    "rust" => rust::refine_comment_type(file_node, &text, file_bytes, comment_type),
+                    _ => comment_type,
+                };
+                let annotation = annotations.grammar(&text);
+                let comment_type = if annotation.is_some() {
+                    CommentType::Annotation
+                } else {
+                    comment_type
+                };
+                let directive = match lang_name {
+                    "golang" => golang::compiler_directive(&text).map(str::to_owned),
+                    _ => None,
+                };
+                let is_docstring = comment_type.is_documentation();
                 if is_docstring && !include_docstrings {
                     continue;
                 }
-                comments.push(CommentInfo::new(
+                let mut comment = CommentInfo::new(
                     text,
                     line_number,
                     file_path.to_owned(),
                     comment_type,
                     is_docstring,
-                ));
+                );
+                annotate_node(&mut comment, file_node, file_bytes, position);
+                if let Some(directive) = &directive {
+                    comment.set_metadata("directive", directive);
+                }
+                if let Some(annotation) = annotation {
+                    comment.set_metadata("annotation", annotation);
+                }
+                let visibility = match lang_name {
+                    "golang" if is_docstring => Some("public"),
+                    "rust" if is_docstring => {
+                        Some(rust::doc_visibility(file_node, file_bytes, comment_type))
+                    }
+                    _ => None,
+                };
+                if let Some(visibility) = visibility {
+                    comment.set_metadata("visibility", visibility);
+                }
+                comment.set_metadata(
+                    "query",
+                    pattern_source(&query, query_pattern, m.pattern_index),
+                );
+                comments.push(comment);
             }
         }
+        extend_license_headers(&mut comments);
         if include_docstrings {
-            let docstrings = self.detect_docstrings(source_bytes, file_path, &lang, lang_name);
+            let docstrings =
+                self.detect_docstrings(source_bytes, file_path, parsed, annotations, context)?;
             comments.extend(docstrings);
         }
-        comments
+        for comment in &mut comments {
+            comment.set_metadata("language", lang_name);
+        }
+        Ok(comments)
+    }
+
+    pub fn line_stats(&self, content: &str, file_path: &str) -> Result<LineStats> {
+        let (lang_name, lang, tree) = self.parse(content, file_path)?;
+        let source_bytes = content.as_bytes();
+        let comment_query = QUERY_TEMPLATES
+            .get(lang_name)
+            .copied()
+            .unwrap_or("(comment) @comment");
+        let annotations = Annotations::for_file(lang_name, content);
+        let mut comment_ranges = Vec::new();
+        let mut comment_rows = HashSet::new();
+        for query_source in
+            std::iter::once(comment_query).chain(DOCSTRING_QUERIES.get(lang_name).copied())
+        {
+            let query = Query::new(&lang, query_source)
+                .with_context(|| format!("invalid {} comment query", lang_name))
+                .stage(Stage::Query)?;
+            let mut cursor = QueryCursor::new();
+            let mut matches = cursor.matches(&query, tree.root_node(), source_bytes);
+            while let Some(m) = matches.next() {
+                for capture in m.captures {
+                    let text = capture.node.utf8_text(source_bytes).unwrap_or_default();
+                    if annotations.grammar(text).is_some() {
+                        continue;
+                    }
+                    comment_ranges.push(capture.node.byte_range());
+                    comment_rows.extend(node_rows(capture.node));
+                }
+            }
+        }
+        let mut code_rows = HashSet::new();
+        let mut stack = vec![tree.root_node()];
+        while let Some(node) = stack.pop() {
+            if comment_ranges
+                .iter()
+                .any(|r| r.start <= node.start_byte() && node.end_byte() <= r.end)
+            {
+                continue;
+            }
+            if node.child_count() == 0 {
+                if node.start_byte() < node.end_byte() {
+                    code_rows.extend(node_rows(node));
+                }
+                continue;
+            }
+            let mut cursor = node.walk();
+            stack.extend(node.children(&mut cursor));
+        }
+        Ok(LineStats::new(comment_rows.len(), code_rows.len()))
+    }
+
+    fn parse(&self, content: &str, file_path: &str) -> Result<(&'static str, Language, Tree)> {
+        let lang_name = self
+            .language_name(file_path)
+            .ok_or_else(|| anyhow!("no language registered for {}", file_path))
+            .stage(Stage::LanguageLookup)?;
+        let lang = get_language(lang_name)
+            .ok_or_else(|| anyhow!("no grammar available for {}", lang_name))
+            .stage(Stage::Grammar)?;
+        let mut parser = Parser::new();
+        parser
+            .set_language(&lang)
+            .with_context(|| format!("incompatible {} grammar", lang_name))
+            .stage(Stage::Grammar)?;
+        let tree = parser
+            .parse(content, None)
+            .ok_or_else(|| anyhow!("failed to parse {} source", lang_name))
+            .stage(Stage::Parse)?;
+        Ok((lang_name, lang, tree))
     }
 
     fn detect_docstrings(
         &self,
         source_bytes: &[u8],
         file_path: &str,
-        lang: &Language,
-        lang_name: &str,
-    ) -> Vec<CommentInfo> {
+        (lang_name, lang, tree): &(&'static str, Language, Tree),
+        annotations: &Annotations,
+        context: Option<&FileContext>,
+    ) -> Result<Vec<CommentInfo>> {
         let doc_query = match DOCSTRING_QUERIES.get(lang_name) {
             Some(q) => *q,
-            None => return Vec::new(),
-        };
-        let mut parser = Parser::new();
-        if parser.set_language(lang).is_err() {
-            return Vec::new();
-        }
-        let tree = match parser.parse(source_bytes, None) {
-            Some(t) => t,
-            None => return Vec::new(),
-        };
-        let query = match Query::new(lang, doc_query) {
-            Ok(q) => q,
-            Err(_) => return Vec::new(),
+            None => return Ok(Vec::new()),
         };
+        let query = Query::new(lang, doc_query)
+            .with_context(|| format!("invalid {} docstring query", lang_name))
+            .stage(Stage::Query)?;
         let pattern = get_docstring_pattern(lang_name);
         let regex = pattern.and_then(|p| Regex::new(p).ok());
         let mut cursor = QueryCursor::new();
         let mut docstrings = Vec::new();
+        let exported = match (*lang_name, context) {
+            ("python", Some(context)) => {
+                python::exported_names(context.tree.root_node(), context.source)
+            }
+            ("python", None) => python::exported_names(tree.root_node(), source_bytes),
+            _ => HashSet::new(),
+        };
+        let test_module = *lang_name == "python" && python::is_test_module(file_path);
         let mut matches = cursor.matches(&query, tree.root_node(), source_bytes);
         while let Some(m) = matches.next() {
             for capture in m.captures {
@@ -143,38 +334,196 @@ impl CommentDetector {
                     continue;
                 }
                 let line_number = node.start_position().row + 1;
-                docstrings.push(CommentInfo::new(
+                let annotation = annotations.grammar(text);
+                let comment_type = if annotation.is_some() {
+                    CommentType::Annotation
+                } else if text.trim_start().starts_with("/**") {
+                    CommentType::OuterDoc
+                } else {
+                    CommentType::Docstring
+                };
+                let mut docstring = CommentInfo::new(
                     text.to_owned(),
                     line_number,
                     file_path.to_owned(),
-                    CommentType::Docstring,
-                    true,
-                ));
+                    comment_type,
+                    comment_type.is_documentation(),
+                );
+                if let Some(annotation) = annotation {
+                    docstring.set_metadata("annotation", annotation);
+                }
+                let (file_node, file_bytes, header_end) = locate(context, node, source_bytes);
+                let position = comment_position(file_node, file_bytes, header_end);
+                annotate_node(&mut docstring, file_node, file_bytes, position);
+                if *lang_name == "python" {
+                    docstring.set_metadata(
+                        "visibility",
+                        python::docstring_visibility(file_node, file_bytes, &exported, test_module),
+                    );
+                }
+                docstring.set_metadata("query", pattern_source(&query, doc_query, m.pattern_index));
+                docstrings.push(docstring);
             }
         }
-        docstrings
+        Ok(docstrings)
     }
+}
 
-    fn determine_comment_type(&self, text: &str, node_type: &str) -> CommentType {
-        let stripped = text.trim();
-        if node_type == "line_comment" {
-            return CommentType::Line;
-        }
-        if node_type == "block_comment" {
-            return CommentType::Block;
+struct FileContext<'a> {
+    source: &'a [u8],
+    tree: Tree,
+    offset: usize,
+    header_end: usize,
+}
+
+impl<'a> FileContext<'a> {
+    fn new(content: &'a str, tree: Tree, offset: usize) -> Self {
+        let header_end = first_code_byte(tree.root_node());
+        Self {
+            source: content.as_bytes(),
+            tree,
+            offset,
+            header_end,
         }
-        if stripped.starts_with("\"\"\"") || stripped.starts_with("'''") {
-            return CommentType::Docstring;
+    }
+
+    fn node(&self, fragment_node: Node) -> Option<Node<'_>> {
+        let start = fragment_node.start_byte() + self.offset;
+        let end = fragment_node.end_byte() + self.offset;
+        let mut node = self
+            .tree
+            .root_node()
+            .descendant_for_byte_range(start, end)?;
+        while node.kind() != fragment_node.kind() {
+            node = node.parent()?;
         }
-        if stripped.starts_with("//") || stripped.starts_with('#') {
-            return CommentType::Line;
+        (node.start_byte() == start && node.end_byte() == end).then_some(node)
+    }
+}
+
+fn locate<'t, 's>(
+    context: Option<&'t FileContext<'s>>,
+    node: Node<'t>,
+    source_bytes: &'t [u8],
+) -> (Node<'t>, &'t [u8], Option<usize>) {
+    match context.and_then(|c| c.node(node).map(|n| (n, c))) {
+        Some((file_node, context)) => (file_node, context.source, Some(context.header_end)),
+        None => (node, source_bytes, None),
+    }
+}
+
+fn extend_license_headers(comments: &mut [CommentInfo]) {
+    let mut start = 0;
+    for end in 1..=comments.len() {
+        if end < comments.len()
+            && is_file_header(&comments[end])
+            && comments[end].line_number()
+                == comments[end - 1].line_number() + comments[end - 1].text().lines().count()
+        {
+            continue;
         }
-        if stripped.starts_with("/*") || stripped.starts_with("<!--") || stripped.starts_with("--")
+        let run = &mut comments[start..end];
+        if run.iter().all(is_file_header)
+            && run
+                .iter()
+                .any(|c| c.comment_type() == CommentType::LicenseHeader)
         {
-            return CommentType::Block;
+            for comment in run.iter_mut().filter(|c| {
+                matches!(
+                    c.comment_type(),
+                    CommentType::Line
+                        | CommentType::Block
+                        | CommentType::Banner
+                        | CommentType::Todo
+                        | CommentType::CommentedOutCode
+                )
+            }) {
+                comment.set_comment_type(CommentType::LicenseHeader);
+            }
+        }
+        start = end;
+    }
+}
+
+fn is_file_header(comment: &CommentInfo) -> bool {
+    comment.metadata("position") == Some(CommentPosition::FileHeader.as_str())
+}
+
+fn pattern_source<'q>(query: &Query, source: &'q str, pattern_index: usize) -> &'q str {
+    source[query.start_byte_for_pattern(pattern_index)..query.end_byte_for_pattern(pattern_index)]
+        .trim()
+}
+
+fn node_rows(node: Node) -> RangeInclusive<usize> {
+    let start = node.start_position().row;
+    let end = node.end_position();
+    if end.row > start && end.column == 0 {
+        start..=end.row - 1
+    } else {
+        start..=end.row
+    }
+}
+
+fn first_code_byte(root: Node) -> usize {
+    let mut cursor = root.walk();
+    root.children(&mut cursor)
+        .find(|child| !child.kind().contains("comment") && child.kind() != "shebang")
+        .map(|child| child.start_byte())
+        .unwrap_or(usize::MAX)
+}
+
+fn comment_position(node: Node, source_bytes: &[u8], header_end: Option<usize>) -> CommentPosition {
+    if header_end.is_some_and(|end| node.start_byte() <= end) {
+        return CommentPosition::FileHeader;
+    }
+    let line_start = source_bytes[..node.start_byte()]
+        .iter()
+        .rposition(|b| *b == b'\n')
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    let has_code_before = source_bytes[line_start..node.start_byte()]
+        .iter()
+        .any(|b| !b.is_ascii_whitespace());
+    if has_code_before {
+        CommentPosition::Trailing
+    } else {
+        CommentPosition::Leading
+    }
+}
+
+fn enclosing_symbol(node: Node, source_bytes: &[u8]) -> Option<String> {
+    let mut names = Vec::new();
+    let mut current = node.parent();
+    while let Some(ancestor) = current {
+        let name = ancestor
+            .child_by_field_name("name")
+            .or_else(|| match ancestor.kind() {
+                "impl_item" => ancestor.child_by_field_name("type"),
+                _ => None,
+            });
+        if let Some(name) = name.and_then(|n| n.utf8_text(source_bytes).ok()) {
+            names.push(name.to_owned());
         }
-        CommentType::Line
+        current = ancestor.parent();
     }
+    if names.is_empty() {
+        return None;
+    }
+    names.reverse();
+    Some(names.join("::"))
+}
+
+fn annotate_node(
+    comment: &mut CommentInfo,
+    node: Node,
+    source_bytes: &[u8],
+    position: CommentPosition,
+) {
+    comment.set_metadata("node_kind", node.kind());
+    if let Some(symbol) = enclosing_symbol(node, source_bytes) {
+        comment.set_metadata("symbol", &symbol);
+    }
+    comment.set_metadata("position", position.as_str());
 }
 
 impl Default for CommentDetector {
@@ -182,3 +531,44 @@ impl Default for CommentDetector {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::CommentDetector;
+    use crate::models::CommentType;
+
+    #[test]
+    fn fragment_annotations_use_the_whole_file() {
+        let detector = CommentDetector::new();
+        let fragment = "/** @type {number} */\nconst x = 1;";
+        let file = format!("// @ts-check\nconst a = 1;\n{}\n", fragment);
+        let in_file = detector
+            .detect_fragment(fragment, Some(&file), "app.js", true)
+            .unwrap();
+        assert_eq!(in_file[0].comment_type(), CommentType::Annotation);
+        let alone = detector
+            .detect_fragment(fragment, None, "app.js", true)
+            .unwrap();
+        assert_eq!(alone[0].comment_type(), CommentType::OuterDoc);
+    }
+
+    #[test]
+    fn fragment_visibility_uses_the_whole_file() {
+        let detector = CommentDetector::new();
+        let fragment = "    def run(self):\n        \"\"\"Run it.\"\"\"\n        return 1\n";
+        let file = format!("class _Private:\n{}", fragment);
+        let docstrings = detector
+            .detect_fragment(fragment, Some(&file), "app.py", true)
+            .unwrap();
+        assert_eq!(docstrings.len(), 1);
+        assert_eq!(docstrings[0].metadata("visibility"), Some("private"));
+        assert_eq!(docstrings[0].symbol(), Some("_Private::run"));
+
+        let fragment = "def _run():\n    \"\"\"Run it.\"\"\"\n";
+        let file = format!("__all__ = [\"_run\"]\n\n{}", fragment);
+        let docstrings = detector
+            .detect_fragment(fragment, Some(&file), "app.py", true)
+            .unwrap();
+        assert_eq!(docstrings[0].metadata("visibility"), Some("public"));
+    }
+}
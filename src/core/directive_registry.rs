@@ -0,0 +1,269 @@
+use std::collections::HashMap;
+
+use once_cell::sync::Lazy;
+
+use crate::config::Config;
+use crate::core::language_registry::canonical_language_name;
+
+const JAVASCRIPT_DIRECTIVES: &[&str] = &[
+    "eslint-disable",
+    "eslint-disable-line",
+    "eslint-disable-next-line",
+    "eslint-enable",
+    "prettier-ignore",
+    "@ts-ignore",
+    "@ts-expect-error",
+    "@ts-nocheck",
+    "@ts-check",
+    "istanbul ignore",
+    "c8 ignore",
+    "biome-ignore",
+    "deno-lint-ignore",
+    "deno-lint-ignore-file",
+    "webpackChunkName:",
+    "@jsx",
+    "@jsxImportSource",
+    "@flow",
+    "<reference",
+];
+
+pub static LANGUAGE_DIRECTIVES: Lazy<HashMap<&'static str, Vec<&'static str>>> = Lazy::new(|| {
+    let mut m = HashMap::new();
+    m.insert(
+        "python",
+        vec![
+            "type: ignore",
+            "noqa",
+            "pyright:",
+            "ruff:",
+            "mypy:",
+            "pylint:",
+            "flake8:",
+            "pyre-ignore",
+            "pyre-fixme",
+            "pyre-strict",
+            "pytype:",
+            "fmt:",
+            "isort:",
+            "yapf:",
+            "pragma:",
+            "nosec",
+        ],
+    );
+    m.insert("javascript", JAVASCRIPT_DIRECTIVES.to_vec());
+    m.insert("typescript", JAVASCRIPT_DIRECTIVES.to_vec());
+    m.insert("tsx", JAVASCRIPT_DIRECTIVES.to_vec());
+    m.insert("golang", vec!["+build", "nolint", "lint:ignore", "#nosec"]);
+    m.insert(
+        "java",
+        vec![
+            "checkstyle:off",
+            "checkstyle:on",
+            "nopmd",
+            "noinspection",
+            "@formatter:off",
+            "@formatter:on",
+            "language=",
+        ],
+    );
+    m.insert(
+        "scala",
+        vec![
+            "format: off",
+            "format: on",
+            "scalastyle:off",
+            "scalastyle:on",
+            "scalafix:off",
+            "scalafix:on",
+            "scalafix:ok",
+        ],
+    );
+    let c_family = vec![
+        "nolint",
+        "nolintnextline",
+        "nolintbegin",
+        "nolintend",
+        "clang-format off",
+        "clang-format on",
+        "iwyu pragma:",
+        "cppcheck-suppress",
+        "lcov_excl_line",
+        "lcov_excl_start",
+        "lcov_excl_stop",
+        "fallthrough",
+    ];
+    m.insert("c", c_family.clone());
+    m.insert("cpp", c_family);
+    m.insert(
+        "ruby",
+        vec![
+            "rubocop:disable",
+            "rubocop:enable",
+            "rubocop:todo",
+            "standard:disable",
+            "standard:enable",
+            "frozen_string_literal:",
+            "encoding:",
+            "warn_indent:",
+            "shareable_constant_value:",
+            ":nocov:",
+        ],
+    );
+    m.insert(
+        "bash",
+        vec![
+            "shellcheck disable=",
+            "shellcheck enable=",
+            "shellcheck source=",
+            "shellcheck shell=",
+        ],
+    );
+    m.insert(
+        "csharp",
+        vec![
+            "resharper disable",
+            "resharper restore",
+            "resharper disable once",
+        ],
+    );
+    m.insert(
+        "swift",
+        vec![
+            "swiftlint:disable",
+            "swiftlint:enable",
+            "swift-format-ignore",
+            "sourcery:",
+        ],
+    );
+    m.insert("elixir", vec!["credo:"]);
+    m.insert("lua", vec!["luacheck:", "stylua: ignore"]);
+    m.insert(
+        "php",
+        vec![
+            "phpcs:",
+            "@phpstan-ignore",
+            "@phpstan-ignore-line",
+            "@phpstan-ignore-next-line",
+            "@psalm-suppress",
+            "@codingstandardsignorestart",
+            "@codingstandardsignoreend",
+            "@codingstandardsignoreline",
+        ],
+    );
+    m.insert("html", vec!["prettier-ignore"]);
+    m.insert(
+        "css",
+        vec![
+            "stylelint-disable",
+            "stylelint-disable-line",
+            "stylelint-disable-next-line",
+            "stylelint-enable",
+            "prettier-ignore",
+        ],
+    );
+    m.insert(
+        "yaml",
+        vec![
+            "yamllint disable",
+            "yamllint disable-line",
+            "yamllint enable",
+            "yaml-language-server:",
+        ],
+    );
+    m
+});
+
+const COMMENT_DELIMITERS: &[&str] = &["<!--", "///", "//", "/*", "#", "--"];
+
+#[derive(Clone)]
+pub struct DirectiveRegistry {
+    languages: HashMap<String, Vec<String>>,
+    global: Vec<String>,
+}
+
+impl DirectiveRegistry {
+    pub fn new() -> Self {
+        Self {
+            languages: LANGUAGE_DIRECTIVES
+                .iter()
+                .map(|(lang, directives)| {
+                    (
+                        (*lang).to_owned(),
+                        directives.iter().map(|d| d.to_lowercase()).collect(),
+                    )
+                })
+                .collect(),
+            global: Vec::new(),
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        let mut registry = Self::new().with_global(&config.filters.directive_prefixes);
+        for (name, language) in &config.languages {
+            let Some(lang_name) = canonical_language_name(name) else {
+                continue;
+            };
+            registry
+                .languages
+                .entry(lang_name.to_owned())
+                .or_default()
+                .extend(language.directives.iter().map(|d| d.trim().to_lowercase()));
+        }
+        registry
+    }
+
+    pub fn with_global(mut self, directives: &[String]) -> Self {
+        self.global
+            .extend(directives.iter().map(|d| d.trim().to_lowercase()));
+        self
+    }
+
+    pub fn matching_directive(&self, language: Option<&str>, text: &str) -> Option<&str> {
+        let normalised = normalise(text);
+        language
+            .and_then(|lang| self.languages.get(lang))
+            .into_iter()
+            .flatten()
+            .chain(&self.global)
+            .find(|directive| matches_token(&normalised, directive))
+            .map(String::as_str)
+    }
+
+    pub fn is_directive(&self, language: Option<&str>, text: &str) -> bool {
+        self.matching_directive(language, text).is_some()
+    }
+}
+
+impl Default for DirectiveRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn normalise(text: &str) -> String {
+    let mut normalised = text.trim();
+    if let Some(delimiter) = COMMENT_DELIMITERS
+        .iter()
+        .find(|d| normalised.starts_with(*d))
+    {
+        normalised = normalised[delimiter.len()..].trim_start();
+    }
+    normalised.to_lowercase()
+}
+
+fn matches_token(normalised: &str, directive: &str) -> bool {
+    if directive.is_empty() {
+        return false;
+    }
+    let Some(rest) = normalised.strip_prefix(directive) else {
+        return false;
+    };
+    if !directive.ends_with(is_word_char) {
+        return true;
+    }
+    !rest.starts_with(|c: char| is_word_char(c) || c == '-')
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
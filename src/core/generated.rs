@@ -0,0 +1,225 @@
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use ignore::gitignore::GitignoreBuilder;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::config::GeneratedConfig;
+
+const HEADER_LINES: usize = 40;
+
+static GENERATED_MARKERS: Lazy<Vec<Regex>> = Lazy::new(|| {
+    vec![
+        Regex::new(r"@generated\b").unwrap(),
+        Regex::new(r"(?i)\bcode generated\b.*\bdo not edit\b").unwrap(),
+        Regex::new(r"(?i)\bgenerated by the protocol buffer compiler\b").unwrap(),
+        Regex::new(r"(?i)\bautogenerated by\b.*\bdo not edit\b").unwrap(),
+    ]
+});
+
+static VENDOR_DIRECTORIES: &[&str] = &[
+    "vendor",
+    "vendored",
+    "third_party",
+    "third-party",
+    "thirdparty",
+    "node_modules",
+    "bower_components",
+    "site-packages",
+    ".venv",
+    "venv",
+    "Pods",
+    "Carthage",
+];
+
+static GENERATED_FILE_NAMES: &[&str] = &[
+    "package-lock.json",
+    "pnpm-lock.yaml",
+    "yarn.lock",
+    "poetry.lock",
+    "pipfile.lock",
+    "gemfile.lock",
+    "composer.lock",
+    "cargo.lock",
+    "go.sum",
+];
+
+static GENERATED_FILE_SUFFIXES: &[&str] = &[
+    "_pb2.py",
+    "_pb2.pyi",
+    "_pb2_grpc.py",
+    ".pb.go",
+    ".pb.cc",
+    ".pb.h",
+    ".pb.swift",
+    ".g.dart",
+    ".designer.cs",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GeneratedReason {
+    Marker(String),
+    GitAttributes(PathBuf),
+    VendorDirectory(String),
+    FileName(String),
+}
+
+impl fmt::Display for GeneratedReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GeneratedReason::Marker(line) => write!(f, "generated marker \"{}\"", line),
+            GeneratedReason::GitAttributes(path) => {
+                write!(f, "linguist-generated in {}", path.display())
+            }
+            GeneratedReason::VendorDirectory(dir) => write!(f, "vendor directory \"{}\"", dir),
+            GeneratedReason::FileName(name) => write!(f, "generated file name \"{}\"", name),
+        }
+    }
+}
+
+pub struct GeneratedFileDetector {
+    enabled: bool,
+    markers: Vec<Regex>,
+    vendor_directories: Vec<String>,
+}
+
+impl GeneratedFileDetector {
+    pub fn new() -> Self {
+        Self {
+            enabled: true,
+            markers: Vec::new(),
+            vendor_directories: Vec::new(),
+        }
+    }
+
+    pub fn from_config(config: &GeneratedConfig) -> Result<Self> {
+        let markers = config
+            .markers
+            .iter()
+            .map(|m| Regex::new(m).with_context(|| format!("invalid generated marker: {}", m)))
+            .collect::<Result<Vec<Regex>>>()?;
+        Ok(Self {
+            enabled: config.enabled,
+            markers,
+            vendor_directories: config.vendor_directories.clone(),
+        })
+    }
+
+    pub fn detect(&self, content: &str, file_path: &Path, root: &Path) -> Option<GeneratedReason> {
+        if !self.enabled {
+            return None;
+        }
+        let relative_path = file_path.strip_prefix(root).unwrap_or(file_path);
+        if let Some(reason) = self.detect_vendor_directory(relative_path) {
+            return Some(reason);
+        }
+        if let Some(reason) = detect_file_name(file_path) {
+            return Some(reason);
+        }
+        if let Some(reason) = self.detect_marker(content) {
+            return Some(reason);
+        }
+        detect_git_attributes(file_path, root)
+    }
+
+    fn detect_vendor_directory(&self, relative_path: &Path) -> Option<GeneratedReason> {
+        let parent = relative_path.parent()?;
+        for component in parent.components() {
+            let name = component.as_os_str().to_string_lossy();
+            let is_vendor = VENDOR_DIRECTORIES.contains(&name.as_ref())
+                || self.vendor_directories.iter().any(|d| *d == name);
+            if is_vendor {
+                return Some(GeneratedReason::VendorDirectory(name.into_owned()));
+            }
+        }
+        None
+    }
+
+    fn detect_marker(&self, content: &str) -> Option<GeneratedReason> {
+        for line in content.lines().take(HEADER_LINES) {
+            let matched = GENERATED_MARKERS
+                .iter()
+                .chain(self.markers.iter())
+                .any(|marker| marker.is_match(line));
+            if matched {
+                return Some(GeneratedReason::Marker(line.trim().to_owned()));
+            }
+        }
+        None
+    }
+}
+
+impl Default for GeneratedFileDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn detect_file_name(file_path: &Path) -> Option<GeneratedReason> {
+    let name = file_path.file_name()?.to_string_lossy().to_lowercase();
+    let generated = GENERATED_FILE_NAMES.contains(&name.as_str())
+        || GENERATED_FILE_SUFFIXES
+            .iter()
+            .any(|suffix| name.ends_with(suffix));
+    generated.then_some(GeneratedReason::FileName(name))
+}
+
+fn detect_git_attributes(file_path: &Path, root: &Path) -> Option<GeneratedReason> {
+    let start = file_path.parent()?;
+    let mut attribute_files = Vec::new();
+    for dir in start.ancestors() {
+        let candidate = dir.join(".gitattributes");
+        if candidate.is_file() {
+            attribute_files.push((dir, candidate));
+        }
+        if dir == root || dir.join(".git").exists() {
+            break;
+        }
+    }
+    let mut generated = None;
+    for (dir, attributes_path) in attribute_files.into_iter().rev() {
+        let Ok(content) = fs::read_to_string(&attributes_path) else {
+            continue;
+        };
+        let Ok(relative_path) = file_path.strip_prefix(dir) else {
+            continue;
+        };
+        for line in content.lines() {
+            let mut parts = line.split_whitespace();
+            let Some(pattern) = parts.next() else {
+                continue;
+            };
+            if pattern.starts_with('#') {
+                continue;
+            }
+            let Some(value) = parts.filter_map(linguist_generated_value).next_back() else {
+                continue;
+            };
+            let mut builder = GitignoreBuilder::new(dir);
+            if builder.add_line(None, pattern).is_err() {
+                continue;
+            }
+            let Ok(matcher) = builder.build() else {
+                continue;
+            };
+            if matcher
+                .matched_path_or_any_parents(relative_path, false)
+                .is_ignore()
+            {
+                generated = value.then(|| attributes_path.clone());
+            }
+        }
+    }
+    generated.map(GeneratedReason::GitAttributes)
+}
+
+fn linguist_generated_value(attribute: &str) -> Option<bool> {
+    match attribute {
+        "linguist-generated" | "linguist-generated=true" => Some(true),
+        "-linguist-generated" | "!linguist-generated" | "linguist-generated=false" => Some(false),
+        _ => None,
+    }
+}
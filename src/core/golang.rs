@@ -0,0 +1,151 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use tree_sitter::Node;
+
+use crate::models::CommentType;
+
+static COMPILER_DIRECTIVE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^//(go:[a-z_]+|export|extern|line|nolint)\b").unwrap());
+
+const ARTICLES: &[&str] = &["A ", "An ", "The "];
+
+pub fn compiler_directive(text: &str) -> Option<&str> {
+    COMPILER_DIRECTIVE
+        .captures(text.trim())
+        .and_then(|c| c.get(1))
+        .map(|m| m.as_str())
+}
+
+pub fn refine_comment_type(
+    node: Node,
+    text: &str,
+    source_bytes: &[u8],
+    comment_type: CommentType,
+) -> CommentType {
+    if compiler_directive(text).is_some() {
+        CommentType::Directive
+    } else if is_godoc(node, source_bytes) {
+        CommentType::OuterDoc
+    } else {
+        comment_type
+    }
+}
+
+fn is_godoc(node: Node, source_bytes: &[u8]) -> bool {
+    let mut first = node;
+    while let Some(prev) = first.prev_sibling()
+        && prev.kind() == "comment"
+        && prev.end_position().row + 1 == first.start_position().row
+    {
+        first = prev;
+    }
+    if first
+        .prev_sibling()
+        .is_some_and(|prev| prev.end_position().row == first.start_position().row)
+    {
+        return false;
+    }
+    let mut last = node;
+    while let Some(next) = last.next_sibling()
+        && next.kind() == "comment"
+        && next.start_position().row == last.end_position().row + 1
+    {
+        last = next;
+    }
+    let Some(declaration) = last.next_sibling() else {
+        return false;
+    };
+    if declaration.start_position().row != last.end_position().row + 1 {
+        return false;
+    }
+    let mut doc = Some(first);
+    while let Some(comment) = doc
+        && compiler_directive(comment.utf8_text(source_bytes).unwrap_or_default()).is_some()
+    {
+        doc = comment.next_sibling().filter(|_| comment.id() != last.id());
+    }
+    let Some(doc) = doc else {
+        return false;
+    };
+    let body = doc.utf8_text(source_bytes).unwrap_or_default();
+    let body = body
+        .trim_start_matches("//")
+        .trim_start_matches("/*")
+        .trim_start();
+    if declaration.kind() == "package_clause" {
+        return declared_name(declaration, source_bytes)
+            .is_some_and(|name| starts_with_name(body, &format!("Package {}", name)));
+    }
+    declared_name(declaration, source_bytes).is_some_and(|name| {
+        name.starts_with(|c: char| c.is_uppercase())
+            && (starts_with_name(body, name)
+                || ARTICLES.iter().any(|a| {
+                    body.strip_prefix(a)
+                        .is_some_and(|b| starts_with_name(b, name))
+                }))
+    })
+}
+
+fn declared_name<'a>(declaration: Node, source_bytes: &'a [u8]) -> Option<&'a str> {
+    let name = match declaration.kind() {
+        "function_declaration"
+        | "method_declaration"
+        | "type_spec"
+        | "type_alias"
+        | "const_spec"
+        | "var_spec" => declaration.child_by_field_name("name")?,
+        "type_declaration" | "const_declaration" | "var_declaration" => {
+            let mut cursor = declaration.walk();
+            let specs = declaration
+                .named_children(&mut cursor)
+                .filter(|c| c.kind() != "comment")
+                .collect::<Vec<Node>>();
+            match specs.as_slice() {
+                [spec] => spec.child_by_field_name("name")?,
+                _ => return None,
+            }
+        }
+        "package_clause" => declaration.named_child(0)?,
+        _ => return None,
+    };
+    name.utf8_text(source_bytes).ok()
+}
+
+fn starts_with_name(body: &str, name: &str) -> bool {
+    body.strip_prefix(name)
+        .is_some_and(|rest| !rest.starts_with(|c: char| c.is_alphanumeric() || c == '_'))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::CommentDetector;
+    use crate::models::CommentType;
+
+    #[test]
+    fn godoc_after_a_directive_is_documentation() {
+        let source = "package main\n\n//go:generate stringer -type=Kind\n// Foo returns one.\nfunc Foo() int { return 1 }\n";
+        let comments = CommentDetector::new()
+            .detect(source, "main.go", true)
+            .unwrap();
+        let types = comments
+            .iter()
+            .map(|c| (c.text(), c.comment_type()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            types,
+            vec![
+                ("//go:generate stringer -type=Kind", CommentType::Directive),
+                ("// Foo returns one.", CommentType::OuterDoc),
+            ]
+        );
+    }
+
+    #[test]
+    fn cgo_is_not_a_compiler_directive() {
+        assert_eq!(super::compiler_directive("//cgo LDFLAGS: -lm"), None);
+        assert_eq!(
+            super::compiler_directive("//go:build linux"),
+            Some("go:build")
+        );
+    }
+}
@@ -3,6 +3,8 @@ use std::collections::HashMap;
 use once_cell::sync::Lazy;
 use tree_sitter::Language;
 
+use crate::config::Config;
+
 pub static EXTENSION_TO_LANGUAGE: Lazy<HashMap<&'static str, &'static str>> = Lazy::new(|| {
     let mut m = HashMap::new();
     m.insert("py", "python");
@@ -39,16 +41,49 @@ pub static EXTENSION_TO_LANGUAGE: Lazy<HashMap<&'static str, &'static str>> = La
     m
 });
 
-pub struct LanguageRegistry;
+#[derive(Clone)]
+pub struct LanguageRegistry {
+    extensions: HashMap<String, &'static str>,
+}
 
 impl LanguageRegistry {
     pub fn new() -> Self {
-        Self
+        Self {
+            extensions: EXTENSION_TO_LANGUAGE
+                .iter()
+                .map(|(ext, lang)| ((*ext).to_owned(), *lang))
+                .collect(),
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        let mut registry = Self::new();
+        for (name, language) in &config.languages {
+            let Some(lang_name) = canonical_language_name(name) else {
+                tracing::warn!("ignoring unknown language in configuration: {}", name);
+                continue;
+            };
+            for ext in &language.extensions {
+                let ext = ext.trim_start_matches('.').to_lowercase();
+                registry.extensions.insert(ext, lang_name);
+            }
+        }
+        registry.extensions.retain(|_, lang_name| {
+            config
+                .languages
+                .get(*lang_name)
+                .is_none_or(|language| language.enabled)
+        });
+        registry
     }
 
     pub fn get_language_name(&self, extension: &str) -> Option<&'static str> {
         let ext = extension.trim_start_matches('.').to_lowercase();
-        EXTENSION_TO_LANGUAGE.get(ext.as_str()).copied()
+        self.extensions.get(ext.as_str()).copied()
+    }
+
+    pub fn is_known_language(&self, name: &str) -> bool {
+        canonical_language_name(name).is_some()
     }
 
     pub fn is_supported(&self, extension: &str) -> bool {
@@ -62,6 +97,13 @@ impl Default for LanguageRegistry {
     }
 }
 
+pub fn canonical_language_name(name: &str) -> Option<&'static str> {
+    EXTENSION_TO_LANGUAGE
+        .values()
+        .find(|lang_name| **lang_name == name)
+        .copied()
+}
+
 pub fn get_language(name: &str) -> Option<Language> {
     match name {
         "python" => Some(tree_sitter_python::LANGUAGE.into()),
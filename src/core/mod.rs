@@ -1,6 +1,15 @@
+mod annotations;
+mod classifier;
 mod detector;
+mod directive_registry;
+mod generated;
+mod golang;
 mod language_registry;
+mod python;
 mod query_templates;
+mod rust;
 
 pub use detector::CommentDetector;
+pub use directive_registry::DirectiveRegistry;
+pub use generated::GeneratedFileDetector;
 pub use language_registry::LanguageRegistry;
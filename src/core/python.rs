@@ -0,0 +1,121 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use tree_sitter::Node;
+
+pub fn exported_names(root: Node, source_bytes: &[u8]) -> HashSet<String> {
+    let mut names = HashSet::new();
+    let mut cursor = root.walk();
+    for statement in root.named_children(&mut cursor) {
+        if statement.kind() != "expression_statement" {
+            continue;
+        }
+        let Some(assignment) = statement.named_child(0) else {
+            continue;
+        };
+        if !matches!(assignment.kind(), "assignment" | "augmented_assignment") {
+            continue;
+        }
+        let is_all = assignment
+            .child_by_field_name("left")
+            .and_then(|l| l.utf8_text(source_bytes).ok())
+            == Some("__all__");
+        let Some(value) = assignment.child_by_field_name("right") else {
+            continue;
+        };
+        if !is_all || !matches!(value.kind(), "list" | "tuple") {
+            continue;
+        }
+        let mut items = value.walk();
+        for item in value.named_children(&mut items) {
+            if item.kind() != "string" {
+                continue;
+            }
+            let text = item.utf8_text(source_bytes).unwrap_or_default();
+            names.insert(text.trim_matches(|c| c == '"' || c == '\'').to_owned());
+        }
+    }
+    names
+}
+
+pub fn is_test_module(file_path: &str) -> bool {
+    let path = Path::new(file_path);
+    let file_name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default();
+    file_name.starts_with("test_")
+        || file_name.ends_with("_test.py")
+        || file_name == "conftest.py"
+        || path
+            .parent()
+            .is_some_and(|dir| dir.iter().any(|c| c == "tests" || c == "test"))
+}
+
+pub fn docstring_visibility(
+    node: Node,
+    source_bytes: &[u8],
+    exported: &HashSet<String>,
+    test_module: bool,
+) -> &'static str {
+    let Some(owner) = node.parent().and_then(|p| p.parent()) else {
+        return "public";
+    };
+    if owner.kind() == "module" {
+        return "module";
+    }
+    let Some(definition) = owner.parent() else {
+        return "public";
+    };
+    if is_public(definition, source_bytes, exported, test_module) {
+        "public"
+    } else {
+        "private"
+    }
+}
+
+fn is_public(
+    definition: Node,
+    source_bytes: &[u8],
+    exported: &HashSet<String>,
+    test_module: bool,
+) -> bool {
+    let name = definition
+        .child_by_field_name("name")
+        .and_then(|n| n.utf8_text(source_bytes).ok())
+        .unwrap_or_default();
+    let container = enclosing_definition(definition);
+    if let Some(container) = container {
+        if container.kind() == "function_definition" {
+            return false;
+        }
+        if !is_public(container, source_bytes, exported, test_module) {
+            return false;
+        }
+    } else if exported.contains(name) {
+        return true;
+    }
+    let is_dunder = name.starts_with("__") && name.ends_with("__");
+    if name.starts_with('_') && !is_dunder {
+        return false;
+    }
+    if !test_module {
+        return true;
+    }
+    match definition.kind() {
+        "function_definition" => name != "test" && !name.starts_with("test_"),
+        "class_definition" => !name.starts_with("Test"),
+        _ => true,
+    }
+}
+
+fn enclosing_definition(definition: Node) -> Option<Node> {
+    let mut parent = definition.parent();
+    while let Some(node) = parent {
+        if matches!(node.kind(), "function_definition" | "class_definition") {
+            return Some(node);
+        }
+        parent = node.parent();
+    }
+    None
+}
@@ -61,9 +61,33 @@ pub static DOCSTRING_QUERIES: Lazy<HashMap<&'static str, &'static str>> = Lazy::
     m.insert(
         "java",
         r#"
-        (comment) @javadoc
+        (block_comment) @javadoc
         (#match? @javadoc "^/\\*\\*")
     "#,
     );
     m
 });
+
+#[cfg(test)]
+mod tests {
+    use tree_sitter::Query;
+
+    use super::{DOCSTRING_QUERIES, QUERY_TEMPLATES};
+    use crate::core::language_registry::get_language;
+
+    #[test]
+    fn queries_compile_against_their_grammars() {
+        for (name, queries) in [
+            ("comment", &*QUERY_TEMPLATES),
+            ("docstring", &*DOCSTRING_QUERIES),
+        ] {
+            for (lang_name, source) in queries.iter() {
+                let lang = get_language(lang_name)
+                    .unwrap_or_else(|| panic!("no grammar for {}", lang_name));
+                if let Err(e) = Query::new(&lang, source) {
+                    panic!("invalid {} {} query: {}", lang_name, name, e);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use tree_sitter::Node;
+
+use crate::models::CommentType;
+
+static SAFETY_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)^(?://+|/\*+)\s*safety:").unwrap());
+
+static UNSAFE_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"\bunsafe\b").unwrap());
+
+pub fn refine_comment_type(
+    node: Node,
+    text: &str,
+    source_bytes: &[u8],
+    comment_type: CommentType,
+) -> CommentType {
+    if SAFETY_PATTERN.is_match(text.trim()) && is_attached_to_unsafe(node, source_bytes) {
+        CommentType::Safety
+    } else {
+        comment_type
+    }
+}
+
+pub fn doc_visibility(node: Node, source_bytes: &[u8], comment_type: CommentType) -> &'static str {
+    let item = match comment_type {
+        CommentType::InnerDoc => node
+            .parent()
+            .and_then(|p| match p.kind() {
+                "declaration_list" => p.parent(),
+                _ => Some(p),
+            })
+            .filter(|p| p.kind() != "source_file"),
+        _ => documented_item(node),
+    };
+    match item {
+        Some(item) if is_public(item, source_bytes) => "public",
+        Some(_) => "private",
+        None => "public",
+    }
+}
+
+fn is_attached_to_unsafe(node: Node, source_bytes: &[u8]) -> bool {
+    let line_start = source_bytes[..node.start_byte()]
+        .iter()
+        .rposition(|b| *b == b'\n')
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    if UNSAFE_PATTERN.is_match(&String::from_utf8_lossy(
+        &source_bytes[line_start..node.start_byte()],
+    )) {
+        return true;
+    }
+    let mut last = node;
+    while let Some(next) = last.next_sibling()
+        && is_comment(next)
+        && next.start_position().row <= last.end_position().row + 1
+    {
+        last = next;
+    }
+    let Some(target) = last.next_sibling() else {
+        return false;
+    };
+    if target.start_position().row > last.end_position().row + 1 {
+        return false;
+    }
+    let text = target.utf8_text(source_bytes).unwrap_or_default();
+    UNSAFE_PATTERN.is_match(text.lines().next().unwrap_or_default())
+}
+
+fn documented_item(node: Node) -> Option<Node> {
+    let mut next = node.next_sibling();
+    while let Some(sibling) = next {
+        if !is_comment(sibling) && sibling.kind() != "attribute_item" {
+            return Some(sibling);
+        }
+        next = sibling.next_sibling();
+    }
+    None
+}
+
+fn is_public(item: Node, source_bytes: &[u8]) -> bool {
+    let mut cursor = item.walk();
+    let visibility = item
+        .children(&mut cursor)
+        .find(|c| c.kind() == "visibility_modifier");
+    if let Some(visibility) = visibility {
+        return visibility.utf8_text(source_bytes).unwrap_or_default() == "pub";
+    }
+    match item.kind() {
+        "enum_variant"
+        | "function_signature_item"
+        | "function_item"
+        | "const_item"
+        | "associated_type" => inherited_visibility(item, source_bytes),
+        _ => false,
+    }
+}
+
+fn inherited_visibility(item: Node, source_bytes: &[u8]) -> bool {
+    let Some(container) = item.parent().and_then(|p| p.parent()) else {
+        return false;
+    };
+    match container.kind() {
+        "enum_item" | "trait_item" => is_public(container, source_bytes),
+        "impl_item" => container.child_by_field_name("trait").is_some(),
+        _ => false,
+    }
+}
+
+fn is_comment(node: Node) -> bool {
+    matches!(node.kind(), "line_comment" | "block_comment")
+}
@@ -0,0 +1,106 @@
+use std::fmt;
+
+use clap::ValueEnum;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Stage {
+    Input,
+    PayloadDecode,
+    Config,
+    LanguageLookup,
+    Grammar,
+    Query,
+    Parse,
+    Unknown,
+}
+
+impl Stage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Stage::Input => "input",
+            Stage::PayloadDecode => "payload-decode",
+            Stage::Config => "config",
+            Stage::LanguageLookup => "language-lookup",
+            Stage::Grammar => "grammar",
+            Stage::Query => "query",
+            Stage::Parse => "parse",
+            Stage::Unknown => "unknown",
+        }
+    }
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug)]
+pub struct StageError {
+    stage: Stage,
+    source: anyhow::Error,
+}
+
+impl StageError {
+    pub fn new(stage: Stage, source: impl Into<anyhow::Error>) -> Self {
+        Self {
+            stage,
+            source: source.into(),
+        }
+    }
+
+    pub fn stage(&self) -> Stage {
+        self.stage
+    }
+}
+
+impl fmt::Display for StageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#}", self.source)
+    }
+}
+
+impl std::error::Error for StageError {}
+
+pub trait StageContext<T> {
+    fn stage(self, stage: Stage) -> anyhow::Result<T>;
+}
+
+impl<T, E> StageContext<T> for Result<T, E>
+where
+    E: Into<anyhow::Error>,
+{
+    fn stage(self, stage: Stage) -> anyhow::Result<T> {
+        self.map_err(|e| StageError::new(stage, e).into())
+    }
+}
+
+pub fn error_stage(error: &anyhow::Error) -> Stage {
+    error
+        .chain()
+        .find_map(|e| e.downcast_ref::<StageError>())
+        .map(StageError::stage)
+        .unwrap_or(Stage::Unknown)
+}
+
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema, ValueEnum,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum FailMode {
+    #[default]
+    Open,
+    Closed,
+}
+
+impl FailMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FailMode::Open => "open",
+            FailMode::Closed => "closed",
+        }
+    }
+}
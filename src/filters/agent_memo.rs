@@ -1,6 +1,9 @@
+use anyhow::{Context, Result};
 use once_cell::sync::Lazy;
 use regex::Regex;
 
+use crate::config::RulePack;
+use crate::filters::{Filter, FilterDecision};
 use crate::models::CommentInfo;
 
 static AGENT_MEMO_PATTERNS: Lazy<Vec<Regex>> = Lazy::new(|| {
@@ -43,26 +46,84 @@ static AGENT_MEMO_PATTERNS: Lazy<Vec<Regex>> = Lazy::new(|| {
     ]
 });
 
-pub struct AgentMemoFilter;
+pub struct AgentMemoFilter {
+    extra_patterns: Vec<Regex>,
+    pack_patterns: Vec<(String, Regex)>,
+}
 
 impl AgentMemoFilter {
     pub fn new() -> Self {
-        Self
+        Self {
+            extra_patterns: Vec::new(),
+            pack_patterns: Vec::new(),
+        }
     }
 
-    pub fn is_agent_memo(&self, comment: &CommentInfo) -> bool {
-        let mut text = comment.text().trim().to_owned();
-        for prefix in &["#", "//", "/*", "--", "*"] {
-            if let Some(rest) = text.strip_prefix(prefix) {
-                text = rest.trim().to_owned();
+    pub fn with_patterns(patterns: &[String]) -> Result<Self> {
+        let extra_patterns = patterns
+            .iter()
+            .map(|p| Regex::new(p).with_context(|| format!("invalid agent memo pattern: {}", p)))
+            .collect::<Result<Vec<Regex>>>()?;
+        Ok(Self {
+            extra_patterns,
+            pack_patterns: Vec::new(),
+        })
+    }
+
+    pub fn with_packs(mut self, packs: &[RulePack]) -> Self {
+        for pack in packs {
+            for pattern in pack.agent_memo_patterns() {
+                self.pack_patterns
+                    .push((pack.id().to_string(), pattern.clone()));
             }
         }
-        for pattern in AGENT_MEMO_PATTERNS.iter() {
+        self
+    }
+
+    pub fn is_agent_memo(&self, comment: &CommentInfo) -> bool {
+        let text = memo_text(comment);
+        for pattern in AGENT_MEMO_PATTERNS.iter().chain(self.extra_patterns.iter()) {
             if pattern.is_match(&text) {
                 return true;
             }
         }
-        false
+        self.pack_patterns
+            .iter()
+            .any(|(_, pattern)| pattern.is_match(&text))
+    }
+
+    pub fn matching_pack(&self, comment: &CommentInfo) -> Option<&str> {
+        let text = memo_text(comment);
+        self.pack_patterns
+            .iter()
+            .find(|(_, pattern)| pattern.is_match(&text))
+            .map(|(pack, _)| pack.as_str())
+    }
+}
+
+fn memo_text(comment: &CommentInfo) -> String {
+    let mut text = comment.text().trim().to_owned();
+    for prefix in &["#", "//", "/*", "--", "*"] {
+        if let Some(rest) = text.strip_prefix(prefix) {
+            text = rest.trim().to_owned();
+        }
+    }
+    text
+}
+
+impl Filter for AgentMemoFilter {
+    fn name(&self) -> &str {
+        "agent-memo"
+    }
+
+    fn evaluate(&self, comment: &CommentInfo) -> FilterDecision {
+        if let Some(pack) = self.matching_pack(comment) {
+            FilterDecision::keep_because(format!("agent memo pattern from {}", pack))
+        } else if self.is_agent_memo(comment) {
+            FilterDecision::keep_because("agent memo pattern")
+        } else {
+            FilterDecision::keep()
+        }
     }
 }
 
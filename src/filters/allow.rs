@@ -0,0 +1,64 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+
+use crate::config::{FilterName, RulePack};
+use crate::filters::{Filter, FilterDecision};
+use crate::models::CommentInfo;
+
+pub struct AllowFilter {
+    patterns: Vec<(String, Regex)>,
+}
+
+impl AllowFilter {
+    pub fn new() -> Self {
+        Self {
+            patterns: Vec::new(),
+        }
+    }
+
+    pub fn with_patterns(patterns: &[String], packs: &[RulePack]) -> Result<Self> {
+        let mut filter = Self::new();
+        for pattern in patterns {
+            let regex = Regex::new(pattern)
+                .with_context(|| format!("invalid allow pattern: {}", pattern))?;
+            filter.patterns.push(("configuration".to_owned(), regex));
+        }
+        for pack in packs {
+            for regex in pack.allow_patterns() {
+                filter.patterns.push((pack.id().to_string(), regex.clone()));
+            }
+        }
+        Ok(filter)
+    }
+
+    pub fn matching_source(&self, comment: &CommentInfo) -> Option<&str> {
+        let text = comment.text().trim();
+        self.patterns
+            .iter()
+            .find(|(_, pattern)| pattern.is_match(text))
+            .map(|(source, _)| source.as_str())
+    }
+}
+
+impl Filter for AllowFilter {
+    fn name(&self) -> &str {
+        "allow"
+    }
+
+    fn evaluate(&self, comment: &CommentInfo) -> FilterDecision {
+        match self.matching_source(comment) {
+            Some(source) => FilterDecision::drop(format!("allow pattern from {}", source)),
+            None => FilterDecision::keep(),
+        }
+    }
+
+    fn config_name(&self) -> Option<FilterName> {
+        Some(FilterName::Allow)
+    }
+}
+
+impl Default for AllowFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -2,6 +2,8 @@ use std::collections::HashSet;
 
 use once_cell::sync::Lazy;
 
+use crate::config::FilterName;
+use crate::filters::{Filter, FilterDecision};
 use crate::models::CommentInfo;
 
 static BDD_KEYWORDS: Lazy<HashSet<&'static str>> = Lazy::new(|| {
@@ -17,14 +19,28 @@ static BDD_KEYWORDS: Lazy<HashSet<&'static str>> = Lazy::new(|| {
     s
 });
 
-pub struct BddFilter;
+pub struct BddFilter {
+    extra_keywords: HashSet<String>,
+}
 
 impl BddFilter {
     pub fn new() -> Self {
-        Self
+        Self {
+            extra_keywords: HashSet::new(),
+        }
+    }
+
+    pub fn with_keywords(keywords: &[String]) -> Self {
+        Self {
+            extra_keywords: keywords.iter().map(|k| k.trim().to_lowercase()).collect(),
+        }
     }
 
     pub fn should_skip(&self, comment: &CommentInfo) -> bool {
+        self.matching_keyword(comment).is_some()
+    }
+
+    pub fn matching_keyword(&self, comment: &CommentInfo) -> Option<String> {
         let mut normalised = comment.text().trim().to_lowercase();
         for prefix in &["#", "//", "--"] {
             if let Some(rest) = normalised.strip_prefix(prefix) {
@@ -32,7 +48,28 @@ impl BddFilter {
                 break;
             }
         }
-        BDD_KEYWORDS.contains(normalised.as_str())
+        if BDD_KEYWORDS.contains(normalised.as_str()) || self.extra_keywords.contains(&normalised) {
+            Some(normalised)
+        } else {
+            None
+        }
+    }
+}
+
+impl Filter for BddFilter {
+    fn name(&self) -> &str {
+        "bdd"
+    }
+
+    fn evaluate(&self, comment: &CommentInfo) -> FilterDecision {
+        match self.matching_keyword(comment) {
+            Some(keyword) => FilterDecision::drop(format!("BDD step keyword `{}`", keyword)),
+            None => FilterDecision::keep(),
+        }
+    }
+
+    fn config_name(&self) -> Option<FilterName> {
+        Some(FilterName::Bdd)
     }
 }
 
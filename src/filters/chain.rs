@@ -0,0 +1,212 @@
+use anyhow::Result;
+
+use crate::config::{Config, FilterName, RulePack};
+use crate::core::DirectiveRegistry;
+use crate::filters::{
+    AgentMemoFilter, AllowFilter, BddFilter, DirectiveFilter, ShebangFilter, SuppressionFilter,
+};
+use crate::models::CommentInfo;
+use crate::policy::Policy;
+
+const DEFAULT_ORDER: &[FilterName] = &[
+    FilterName::Allow,
+    FilterName::Bdd,
+    FilterName::Directive,
+    FilterName::Shebang,
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterDecision {
+    Keep(Option<String>),
+    Drop(String),
+}
+
+impl FilterDecision {
+    pub fn keep() -> Self {
+        FilterDecision::Keep(None)
+    }
+
+    pub fn keep_because(reason: impl Into<String>) -> Self {
+        FilterDecision::Keep(Some(reason.into()))
+    }
+
+    pub fn drop(reason: impl Into<String>) -> Self {
+        FilterDecision::Drop(reason.into())
+    }
+
+    pub fn is_drop(&self) -> bool {
+        matches!(self, FilterDecision::Drop(_))
+    }
+
+    pub fn reason(&self) -> Option<&str> {
+        match self {
+            FilterDecision::Keep(reason) => reason.as_deref(),
+            FilterDecision::Drop(reason) => Some(reason),
+        }
+    }
+}
+
+pub trait Filter {
+    fn name(&self) -> &str;
+
+    fn evaluate(&self, comment: &CommentInfo) -> FilterDecision;
+
+    fn config_name(&self) -> Option<FilterName> {
+        None
+    }
+}
+
+impl<F: Filter + ?Sized> Filter for &F {
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    fn evaluate(&self, comment: &CommentInfo) -> FilterDecision {
+        (**self).evaluate(comment)
+    }
+
+    fn config_name(&self) -> Option<FilterName> {
+        (**self).config_name()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilterTrace {
+    filter: String,
+    decision: FilterDecision,
+}
+
+impl FilterTrace {
+    pub fn filter(&self) -> &str {
+        &self.filter
+    }
+
+    pub fn decision(&self) -> &FilterDecision {
+        &self.decision
+    }
+}
+
+struct MemoOnlyFilter<'a> {
+    agent_memo_filter: &'a AgentMemoFilter,
+}
+
+impl Filter for MemoOnlyFilter<'_> {
+    fn name(&self) -> &str {
+        "agent-memo"
+    }
+
+    fn evaluate(&self, comment: &CommentInfo) -> FilterDecision {
+        match self.agent_memo_filter.evaluate(comment) {
+            FilterDecision::Keep(None) => {
+                FilterDecision::drop("not an agent memo under the memo-only profile")
+            }
+            decision => decision,
+        }
+    }
+}
+
+pub struct FilterChain<'a> {
+    policy: &'a Policy<'a>,
+    filters: Vec<Box<dyn Filter + 'a>>,
+}
+
+impl<'a> FilterChain<'a> {
+    pub fn new(policy: &'a Policy<'a>) -> Self {
+        Self {
+            policy,
+            filters: Vec::new(),
+        }
+    }
+
+    pub fn from_config(
+        config: &Config,
+        packs: &[RulePack],
+        policy: &'a Policy<'a>,
+        agent_memo_filter: &'a AgentMemoFilter,
+        suppression_filter: &'a SuppressionFilter,
+    ) -> Result<Self> {
+        let filters = &config.filters;
+        let mut bdd_keywords = filters.bdd_keywords.clone();
+        let mut directives = DirectiveRegistry::from_config(config);
+        for pack in packs {
+            bdd_keywords.extend_from_slice(pack.bdd_keywords());
+            directives = directives.with_global(pack.directive_prefixes());
+        }
+        let mut chain = Self::new(policy)
+            .with_filter(suppression_filter)
+            .with_filter(policy);
+        let order = filters
+            .order
+            .iter()
+            .chain(DEFAULT_ORDER.iter().filter(|f| !filters.order.contains(f)));
+        for name in order {
+            chain = match name {
+                FilterName::Allow => {
+                    chain.with_filter(AllowFilter::with_patterns(&filters.allow_patterns, packs)?)
+                }
+                FilterName::Bdd => chain.with_filter(BddFilter::with_keywords(&bdd_keywords)),
+                FilterName::Directive => {
+                    chain.with_filter(DirectiveFilter::with_registry(directives.clone()))
+                }
+                FilterName::Shebang => chain.with_filter(ShebangFilter::new()),
+            };
+        }
+        if policy.profile().blocks_only_agent_memos() {
+            chain = chain.with_filter(MemoOnlyFilter { agent_memo_filter });
+        } else {
+            chain = chain.with_filter(agent_memo_filter);
+        }
+        Ok(chain)
+    }
+
+    pub fn with_filter(mut self, filter: impl Filter + 'a) -> Self {
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    pub fn evaluate(&self, comment: &CommentInfo) -> Vec<FilterTrace> {
+        let mut trace = Vec::new();
+        for filter in &self.filters {
+            if let Some(name) = filter.config_name()
+                && !self.policy.filter_enabled(comment.language(), name)
+            {
+                continue;
+            }
+            let decision = filter.evaluate(comment);
+            let is_drop = decision.is_drop();
+            trace.push(FilterTrace {
+                filter: filter.name().to_owned(),
+                decision,
+            });
+            if is_drop {
+                break;
+            }
+        }
+        trace
+    }
+
+    pub fn apply(&self, comments: Vec<CommentInfo>) -> Vec<CommentInfo> {
+        comments
+            .into_iter()
+            .filter(|c| {
+                let trace = self.evaluate(c);
+                for step in &trace {
+                    if let Some(reason) = step.decision().reason() {
+                        tracing::debug!(
+                            "line {}: {} {} ({})",
+                            c.line_number(),
+                            step.filter(),
+                            if step.decision().is_drop() {
+                                "dropped"
+                            } else {
+                                "kept"
+                            },
+                            reason
+                        );
+                    }
+                }
+                !trace.last().is_some_and(|t| t.decision().is_drop())
+            })
+            .collect()
+    }
+}
@@ -1,55 +1,48 @@
-use once_cell::sync::Lazy;
-
+use crate::config::FilterName;
+use crate::core::DirectiveRegistry;
+use crate::filters::{Filter, FilterDecision};
 use crate::models::CommentInfo;
 
-static TYPE_CHECKER_PREFIXES: Lazy<Vec<&'static str>> = Lazy::new(|| {
-    vec![
-        "type:",
-        "noqa",
-        "pyright:",
-        "ruff:",
-        "mypy:",
-        "pylint:",
-        "flake8:",
-        "pyre:",
-        "pytype:",
-        "eslint-disable",
-        "eslint-ignore",
-        "prettier-ignore",
-        "ts-ignore",
-        "ts-expect-error",
-        "clippy:",
-        "allow",
-        "deny",
-        "warn",
-        "forbid",
-    ]
-});
-
-pub struct DirectiveFilter;
+pub struct DirectiveFilter {
+    registry: DirectiveRegistry,
+}
 
 impl DirectiveFilter {
     pub fn new() -> Self {
-        Self
+        Self {
+            registry: DirectiveRegistry::new(),
+        }
+    }
+
+    pub fn with_registry(registry: DirectiveRegistry) -> Self {
+        Self { registry }
     }
 
     pub fn should_skip(&self, comment: &CommentInfo) -> bool {
-        let mut normalised = comment.text().trim().to_lowercase();
-        for prefix in &["#", "//", "/*", "--"] {
-            if let Some(rest) = normalised.strip_prefix(prefix) {
-                normalised = rest.trim().to_owned();
-                break;
-            }
-        }
-        if let Some(rest) = normalised.strip_prefix('@') {
-            normalised = rest.trim().to_owned();
-        }
-        for directive in TYPE_CHECKER_PREFIXES.iter() {
-            if normalised.starts_with(directive) {
-                return true;
-            }
+        self.matching_directive(comment).is_some()
+    }
+
+    pub fn matching_directive<'a>(&'a self, comment: &'a CommentInfo) -> Option<&'a str> {
+        self.registry
+            .matching_directive(comment.language(), comment.text())
+            .or_else(|| comment.metadata("directive"))
+    }
+}
+
+impl Filter for DirectiveFilter {
+    fn name(&self) -> &str {
+        "directive"
+    }
+
+    fn evaluate(&self, comment: &CommentInfo) -> FilterDecision {
+        match self.matching_directive(comment) {
+            Some(directive) => FilterDecision::drop(format!("tool directive `{}`", directive)),
+            None => FilterDecision::keep(),
         }
-        false
+    }
+
+    fn config_name(&self) -> Option<FilterName> {
+        Some(FilterName::Directive)
     }
 }
 
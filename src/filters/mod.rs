@@ -1,9 +1,15 @@
 mod agent_memo;
+mod allow;
 mod bdd;
+mod chain;
 mod directive;
 mod shebang;
+mod suppression;
 
 pub use agent_memo::AgentMemoFilter;
+pub use allow::AllowFilter;
 pub use bdd::BddFilter;
+pub use chain::{Filter, FilterChain, FilterDecision, FilterTrace};
 pub use directive::DirectiveFilter;
 pub use shebang::ShebangFilter;
+pub use suppression::SuppressionFilter;
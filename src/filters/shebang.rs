@@ -1,3 +1,5 @@
+use crate::config::FilterName;
+use crate::filters::{Filter, FilterDecision};
 use crate::models::CommentInfo;
 
 pub struct ShebangFilter;
@@ -12,6 +14,24 @@ impl ShebangFilter {
     }
 }
 
+impl Filter for ShebangFilter {
+    fn name(&self) -> &str {
+        "shebang"
+    }
+
+    fn evaluate(&self, comment: &CommentInfo) -> FilterDecision {
+        if self.should_skip(comment) {
+            FilterDecision::drop("shebang line")
+        } else {
+            FilterDecision::keep()
+        }
+    }
+
+    fn config_name(&self) -> Option<FilterName> {
+        Some(FilterName::Shebang)
+    }
+}
+
 impl Default for ShebangFilter {
     fn default() -> Self {
         Self::new()
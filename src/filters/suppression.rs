@@ -0,0 +1,237 @@
+use std::collections::HashSet;
+
+use jiff::Zoned;
+use jiff::civil::Date;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::filters::{Filter, FilterDecision};
+use crate::models::CommentInfo;
+
+static MARKER_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)\bdecomment:\s*(allow-next|allow|off|on)\b(?:\s+until=([\w-]+))?(?:\s+--\s*(.*))?",
+    )
+    .unwrap()
+});
+
+const CLOSING_DELIMITERS: &[&str] = &["*/", "-->", "\"\"\"", "'''", "]]", "#}", "--}}"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MarkerKind {
+    Allow,
+    AllowNext,
+    Off,
+    On,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Expiry {
+    Never,
+    Until(Date),
+    Invalid(String),
+}
+
+#[derive(Debug, Clone)]
+struct Marker {
+    kind: MarkerKind,
+    expiry: Expiry,
+    reason: Option<String>,
+}
+
+impl Marker {
+    fn parse(text: &str) -> Option<Self> {
+        let captures = MARKER_PATTERN.captures(text)?;
+        let kind = match captures[1].to_lowercase().as_str() {
+            "allow-next" => MarkerKind::AllowNext,
+            "allow" => MarkerKind::Allow,
+            "off" => MarkerKind::Off,
+            _ => MarkerKind::On,
+        };
+        let expiry = match captures.get(2) {
+            Some(until) => match until.as_str().parse::<Date>() {
+                Ok(date) => Expiry::Until(date),
+                Err(_) => Expiry::Invalid(until.as_str().to_owned()),
+            },
+            None => Expiry::Never,
+        };
+        let reason = captures
+            .get(3)
+            .map(|r| trim_closing_delimiters(r.as_str()))
+            .filter(|r| !r.is_empty());
+        Some(Self {
+            kind,
+            expiry,
+            reason,
+        })
+    }
+
+    fn expiry_problem(&self, today: Date) -> Option<String> {
+        match &self.expiry {
+            Expiry::Never => None,
+            Expiry::Until(date) if *date >= today => None,
+            Expiry::Until(date) => Some(format!("suppression expired on {}", date)),
+            Expiry::Invalid(value) => Some(format!("invalid suppression expiry: {}", value)),
+        }
+    }
+}
+
+fn trim_closing_delimiters(reason: &str) -> String {
+    let mut reason = reason.trim();
+    while let Some(stripped) = CLOSING_DELIMITERS
+        .iter()
+        .find_map(|d| reason.strip_suffix(d))
+    {
+        reason = stripped.trim_end();
+    }
+    reason.to_owned()
+}
+
+pub struct SuppressionFilter {
+    suppressed: HashSet<(Option<usize>, usize, String)>,
+    expired: Vec<CommentInfo>,
+}
+
+impl SuppressionFilter {
+    pub fn new(comments: &[CommentInfo]) -> Self {
+        let mut filter = Self {
+            suppressed: HashSet::new(),
+            expired: Vec::new(),
+        };
+        filter.extend(comments);
+        filter
+    }
+
+    pub fn extend(&mut self, comments: &[CommentInfo]) {
+        let today = Zoned::now().date();
+        let mut ordered = comments.iter().collect::<Vec<&CommentInfo>>();
+        ordered.sort_by_key(|c| c.line_number());
+        let mut region_start = None;
+        let mut pending_next = None;
+        for comment in ordered {
+            let marker = Marker::parse(comment.text());
+            if let Some(marker) = &marker {
+                self.suppressed.insert(comment.location_key());
+                if let Some(problem) = marker.expiry_problem(today) {
+                    let mut comment = comment.clone();
+                    comment.set_metadata("message", &problem);
+                    self.expired.push(comment);
+                    continue;
+                }
+                if let Some(reason) = &marker.reason {
+                    tracing::debug!(
+                        "line {}: decomment suppression ({})",
+                        comment.line_number(),
+                        reason
+                    );
+                }
+                match marker.kind {
+                    MarkerKind::Allow => {}
+                    MarkerKind::AllowNext => pending_next = Some(comment.line_number()),
+                    MarkerKind::Off => region_start = Some(comment.line_number()),
+                    MarkerKind::On => region_start = None,
+                }
+                continue;
+            }
+            if let Some(start) = region_start {
+                tracing::debug!(
+                    "line {} suppressed by region starting at line {}",
+                    comment.line_number(),
+                    start
+                );
+                self.suppressed.insert(comment.location_key());
+            } else if let Some(marker_line) = pending_next.take() {
+                tracing::debug!(
+                    "line {} suppressed by allow-next at line {}",
+                    comment.line_number(),
+                    marker_line
+                );
+                self.suppressed.insert(comment.location_key());
+            }
+        }
+    }
+
+    pub fn should_skip(&self, comment: &CommentInfo) -> bool {
+        self.suppressed.contains(&comment.location_key())
+    }
+
+    pub fn expired(&self) -> &[CommentInfo] {
+        &self.expired
+    }
+}
+
+impl Filter for SuppressionFilter {
+    fn name(&self) -> &str {
+        "suppression"
+    }
+
+    fn evaluate(&self, comment: &CommentInfo) -> FilterDecision {
+        if self.should_skip(comment) {
+            FilterDecision::drop("inline decomment marker")
+        } else {
+            FilterDecision::keep()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SuppressionFilter;
+    use crate::models::{CommentInfo, CommentType};
+
+    fn comment(text: &str, line_number: usize, edit: Option<usize>) -> CommentInfo {
+        let mut comment = CommentInfo::new(
+            text.to_owned(),
+            line_number,
+            "app.py".to_owned(),
+            CommentType::Line,
+            false,
+        );
+        if let Some(edit) = edit {
+            comment.set_metadata("edit", &edit.to_string());
+        }
+        comment
+    }
+
+    #[test]
+    fn allow_next_suppresses_the_following_comment() {
+        let comments = vec![
+            comment("# decomment: allow-next -- why", 1, None),
+            comment("# retry the request", 2, None),
+            comment("# another note", 3, None),
+        ];
+        let filter = SuppressionFilter::new(&comments);
+        assert!(filter.should_skip(&comments[1]));
+        assert!(!filter.should_skip(&comments[2]));
+    }
+
+    #[test]
+    fn markers_do_not_leak_across_edits() {
+        let first = vec![
+            comment("# decomment: allow-next -- why", 1, Some(1)),
+            comment("# retry the request", 2, Some(1)),
+        ];
+        let second = vec![comment("# retry the request", 2, Some(2))];
+        let mut filter = SuppressionFilter::new(&first);
+        filter.extend(&second);
+        assert!(filter.should_skip(&first[1]));
+        assert!(!filter.should_skip(&second[0]));
+    }
+
+    #[test]
+    fn expired_markers_are_reported_per_edit() {
+        let first = vec![comment("# decomment: off until=2000-01-01", 1, Some(1))];
+        let second = vec![comment("# decomment: off until=2000-01-01", 1, Some(2))];
+        let mut filter = SuppressionFilter::new(&first);
+        filter.extend(&second);
+        let expired = filter
+            .expired()
+            .iter()
+            .map(CommentInfo::location_key)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            expired,
+            vec![first[0].location_key(), second[0].location_key()]
+        );
+    }
+}
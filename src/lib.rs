@@ -0,0 +1,11 @@
+pub mod baseline;
+pub mod catalog;
+pub mod commands;
+pub mod config;
+pub mod core;
+pub mod error;
+pub mod filters;
+pub mod models;
+pub mod output;
+pub mod pause;
+pub mod policy;
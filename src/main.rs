@@ -1,21 +1,32 @@
-mod core;
-mod filters;
-mod models;
-mod output;
-
 use std::collections::HashSet;
+use std::fs;
 use std::io::{self, Read};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 
-use anyhow::Result;
-use clap::Parser;
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
 use serde::Deserialize;
 
-use crate::core::{CommentDetector, LanguageRegistry};
-use crate::filters::{BddFilter, DirectiveFilter, ShebangFilter};
-use crate::models::CommentInfo;
-use crate::output::format_hook_message;
+use claude_decomment_hook::baseline::Baseline;
+use claude_decomment_hook::catalog::{
+    CUSTOM_RULE, EXPIRED_SUPPRESSION, MISSING_LICENSE_HEADER, classify, tag_comment,
+};
+use claude_decomment_hook::commands::{
+    run_baseline_check, run_baseline_update, run_config_check, run_config_schema, run_explain,
+};
+use claude_decomment_hook::config::{ConfigLoader, ShadowConfig, load_packs};
+use claude_decomment_hook::core::{CommentDetector, LanguageRegistry};
+use claude_decomment_hook::error::{FailMode, Stage, StageContext, error_stage};
+use claude_decomment_hook::filters::{AgentMemoFilter, FilterChain, SuppressionFilter};
+use claude_decomment_hook::models::{CommentInfo, Finding, Severity};
+use claude_decomment_hook::output::{
+    ExplainTrace, ShadowRecord, append_shadow_record, format_additional_context,
+    format_error_message, format_error_report, format_hook_message, format_info_message,
+    format_license_message, format_warning_message,
+};
+use claude_decomment_hook::pause::check_pause;
+use claude_decomment_hook::policy::{Budget, LicenseCheck, Policy, Profile, RuleEngine};
 
 const EXIT_PASS: u8 = 0;
 const EXIT_BLOCK: u8 = 2;
@@ -32,6 +43,68 @@ struct Cli {
         help = "Custom prompt to replace the default warning message. Use {{comments}} placeholder for detected comments XML."
     )]
     prompt: Option<String>,
+    #[arg(
+        long,
+        value_enum,
+        help = "Policy profile to apply, overriding the configured profile."
+    )]
+    profile: Option<Profile>,
+    #[arg(
+        long,
+        help = "Run in observe-only mode: record what the hook would have done and always pass."
+    )]
+    shadow: bool,
+    #[arg(
+        long,
+        value_enum,
+        help = "Whether internal errors let the tool call through (open) or block it (closed)."
+    )]
+    on_error: Option<FailMode>,
+    #[arg(
+        long,
+        value_name = "FILE",
+        num_args = 0..=1,
+        require_equals = true,
+        help = "Trace every captured comment through each stage to stderr, or append it to FILE."
+    )]
+    explain: Option<Option<PathBuf>>,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    #[command(subcommand, about = "Inspect and validate configuration")]
+    Config(ConfigCommand),
+    #[command(subcommand, about = "Record or check accepted existing comments")]
+    Baseline(BaselineCommand),
+    #[command(about = "Describe a finding's rule ID, or list all rules")]
+    Explain { rule: Option<String> },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommand {
+    #[command(about = "Validate and print the effective configuration for a path")]
+    Check {
+        #[arg(default_value = ".")]
+        path: PathBuf,
+    },
+    #[command(about = "Print the JSON Schema for .decomment.toml")]
+    Schema,
+}
+
+#[derive(Subcommand)]
+enum BaselineCommand {
+    #[command(about = "Record fingerprints of all current comments under a path")]
+    Update {
+        #[arg(default_value = ".")]
+        path: PathBuf,
+    },
+    #[command(about = "Report baseline entries that no longer match any comment")]
+    Check {
+        #[arg(default_value = ".")]
+        path: PathBuf,
+    },
 }
 
 #[derive(Debug, Deserialize)]
@@ -51,6 +124,9 @@ struct ToolInput {
 
 #[derive(Debug, Deserialize)]
 struct HookInput {
+    session_id: Option<String>,
+    cwd: Option<String>,
+    hook_event_name: Option<String>,
     tool_name: Option<String>,
     tool_input: ToolInput,
 }
@@ -63,33 +139,129 @@ fn main() -> ExitCode {
                 .add_directive(tracing::Level::INFO.into()),
         )
         .init();
-    match run() {
+    let cli = Cli::parse();
+    if let Some(command) = &cli.command {
+        return match run_command(command) {
+            Ok(code) => ExitCode::from(code),
+            Err(e) => {
+                eprintln!("error: {:#}", e);
+                ExitCode::FAILURE
+            }
+        };
+    }
+    let mut errors = ErrorHandling::new(&cli);
+    match run(&cli, &mut errors) {
         Ok(code) => ExitCode::from(code),
-        Err(_) => {
-            tracing::warn!("skipping: internal error");
-            ExitCode::from(EXIT_PASS)
+        Err(e) => ExitCode::from(report_error(&e, &errors)),
+    }
+}
+
+struct ErrorHandling {
+    fail_mode: FailMode,
+    shadow: bool,
+    shadow_log: PathBuf,
+    session_id: Option<String>,
+    tool_name: String,
+    file_path: String,
+}
+
+impl ErrorHandling {
+    fn new(cli: &Cli) -> Self {
+        Self {
+            fail_mode: cli.on_error.unwrap_or_default(),
+            shadow: cli.shadow,
+            shadow_log: ShadowConfig::default().log,
+            session_id: None,
+            tool_name: String::new(),
+            file_path: String::new(),
         }
     }
 }
 
-fn run() -> Result<u8> {
-    let cli = Cli::parse();
-    let mut input = String::new();
-    if io::stdin().read_to_string(&mut input).is_err() {
-        tracing::warn!("skipping: failed to read stdin");
-        return Ok(EXIT_PASS);
+fn run_command(command: &Command) -> Result<u8> {
+    match command {
+        Command::Config(ConfigCommand::Check { path }) => run_config_check(path),
+        Command::Config(ConfigCommand::Schema) => run_config_schema(),
+        Command::Baseline(BaselineCommand::Update { path }) => run_baseline_update(path),
+        Command::Baseline(BaselineCommand::Check { path }) => run_baseline_check(path),
+        Command::Explain { rule } => run_explain(rule.as_deref()),
+    }
+}
+
+fn report_error(error: &anyhow::Error, errors: &ErrorHandling) -> u8 {
+    let stage = error_stage(error);
+    let report = format_error_report(stage, error, errors.fail_mode);
+    if errors.shadow {
+        let exit_code = match errors.fail_mode {
+            FailMode::Open => EXIT_PASS,
+            FailMode::Closed => EXIT_BLOCK,
+        };
+        let record = ShadowRecord::new(
+            errors.session_id.as_deref(),
+            &errors.tool_name,
+            &errors.file_path,
+            "error",
+            exit_code,
+            &[],
+            Some(&report),
+        );
+        if let Err(e) = append_shadow_record(&errors.shadow_log, &record) {
+            tracing::warn!(
+                "failed to write shadow log {}: {:#}",
+                errors.shadow_log.display(),
+                e
+            );
+        }
+        tracing::info!(
+            "shadow: internal error in {} stage would have exited with {}",
+            stage,
+            exit_code
+        );
+        eprintln!("{}", report);
+        return EXIT_PASS;
+    }
+    match errors.fail_mode {
+        FailMode::Open => {
+            tracing::warn!("skipping: internal error in {} stage", stage);
+            eprintln!("{}", report);
+            EXIT_PASS
+        }
+        FailMode::Closed => {
+            tracing::error!("blocking: internal error in {} stage", stage);
+            eprint!("{}", format_error_message(stage, error));
+            eprintln!("{}", report);
+            EXIT_BLOCK
+        }
+    }
+}
+
+fn run(cli: &Cli, errors: &mut ErrorHandling) -> Result<u8> {
+    let mut trace = ExplainTrace::new(cli.explain.is_some());
+    let result = check(cli, errors, &mut trace);
+    if let Err(e) = &result {
+        trace.note(format!("internal error: {:#}", e));
     }
+    if let Err(e) = trace.write(cli.explain.as_ref().and_then(|t| t.as_deref())) {
+        tracing::warn!("{:#}", e);
+    }
+    result
+}
+
+fn check(cli: &Cli, errors: &mut ErrorHandling, trace: &mut ExplainTrace) -> Result<u8> {
+    let mut input = String::new();
+    io::stdin()
+        .read_to_string(&mut input)
+        .context("failed to read stdin")
+        .stage(Stage::Input)?;
     if input.is_empty() {
         tracing::warn!("skipping: no input provided");
         return Ok(EXIT_PASS);
     }
-    let hook_input = match serde_json::from_str::<HookInput>(&input) {
-        Ok(h) => h,
-        Err(_) => {
-            tracing::warn!("skipping: invalid input format");
-            return Ok(EXIT_PASS);
-        }
-    };
+    let hook_input = serde_json::from_str::<HookInput>(&input)
+        .context("invalid input format")
+        .stage(Stage::PayloadDecode)?;
+    errors.session_id = hook_input.session_id.clone();
+    errors.tool_name = hook_input.tool_name.clone().unwrap_or_default();
     let file_path = match &hook_input.tool_input.file_path {
         Some(p) if !p.is_empty() => p.clone(),
         _ => {
@@ -97,6 +269,38 @@ fn run() -> Result<u8> {
             return Ok(EXIT_PASS);
         }
     };
+    trace.set_file_path(&file_path);
+    errors.file_path = file_path.clone();
+    let resolved_path = resolve_path(&file_path, hook_input.cwd.as_deref());
+    if let Some(reason) = check_pause(&resolved_path, hook_input.session_id.as_deref()) {
+        tracing::info!("skipping: checks are paused ({})", reason);
+        trace.note(format!("skipped: checks are paused ({})", reason));
+        return Ok(EXIT_PASS);
+    }
+    let loaded = ConfigLoader::load(&resolved_path).stage(Stage::Config)?;
+    tracing::debug!("project root: {}", loaded.root().display());
+    for source in loaded.sources() {
+        tracing::debug!("loaded configuration from {}", source.display());
+    }
+    let config = loaded.config_for(&resolved_path).stage(Stage::Config)?;
+    errors.fail_mode = cli.on_error.unwrap_or(config.on_error);
+    errors.shadow = cli.shadow || config.shadow.enabled;
+    errors.shadow_log = loaded.root().join(&config.shadow.log);
+    errors.file_path = loaded
+        .relative_path(&resolved_path)
+        .to_string_lossy()
+        .into_owned();
+    if loaded
+        .is_excluded(&config, &resolved_path)
+        .stage(Stage::Config)?
+    {
+        tracing::info!(
+            "skipping: {} is excluded by configuration",
+            loaded.relative_path(&resolved_path).display()
+        );
+        trace.note("skipped: excluded by configuration");
+        return Ok(EXIT_PASS);
+    }
     let ext = Path::new(&file_path)
         .extension()
         .and_then(|e| e.to_str())
@@ -108,13 +312,32 @@ fn run() -> Result<u8> {
                 .unwrap_or("")
                 .to_lowercase()
         });
-    let registry = LanguageRegistry::new();
+    let registry = LanguageRegistry::from_config(&config);
     if !registry.is_supported(&ext) {
         tracing::warn!("skipping: non-code file");
+        trace.note(format!("skipped: no language registered for `{}`", ext));
+        return Ok(EXIT_PASS);
+    }
+    let detector = CommentDetector::from_config(&config).stage(Stage::Config)?;
+    let profile = cli.profile.unwrap_or(config.profile);
+    let policy = Policy::new(&config, profile);
+    let include_docstrings = policy.detects_docstrings(detector.language_name(&file_path));
+    let file_content = fs::read_to_string(&resolved_path).ok();
+    let written_content = file_content
+        .clone()
+        .unwrap_or_else(|| get_content_to_check(&hook_input));
+    if let Some(reason) = detector.detect_generated(&written_content, &resolved_path, loaded.root())
+    {
+        tracing::info!(
+            "skipping: {} is generated or vendored ({})",
+            loaded.relative_path(&resolved_path).display(),
+            reason
+        );
+        trace.note(format!("skipped: generated or vendored ({})", reason));
         return Ok(EXIT_PASS);
     }
-    let detector = CommentDetector::new();
     let tool_name = hook_input.tool_name.as_deref().unwrap_or("");
+    let mut suppression_filter = SuppressionFilter::new(&[]);
     let comments = match tool_name {
         "Edit" => {
             let new_string = match &hook_input.tool_input.new_string {
@@ -125,7 +348,21 @@ fn run() -> Result<u8> {
                 }
             };
             let old_string = hook_input.tool_input.old_string.as_deref().unwrap_or("");
-            detect_new_comments_for_edit(&detector, old_string, new_string, &file_path)
+            let detected = detector.detect_fragment(
+                new_string,
+                file_content.as_deref(),
+                &file_path,
+                include_docstrings,
+            )?;
+            suppression_filter.extend(&detected);
+            detect_new_comments_for_edit(
+                &detector,
+                old_string,
+                detected,
+                &file_path,
+                include_docstrings,
+                trace,
+            )?
         }
         "MultiEdit" => {
             let edits = match &hook_input.tool_input.edits {
@@ -136,14 +373,30 @@ fn run() -> Result<u8> {
                 }
             };
             let mut all_comments = Vec::new();
-            for edit in edits {
+            for (index, edit) in edits.iter().enumerate() {
                 let new_string = match &edit.new_string {
                     Some(s) if !s.is_empty() => s,
                     _ => continue,
                 };
                 let old_string = edit.old_string.as_deref().unwrap_or("");
-                let edit_comments =
-                    detect_new_comments_for_edit(&detector, old_string, new_string, &file_path);
+                let mut detected = detector.detect_fragment(
+                    new_string,
+                    file_content.as_deref(),
+                    &file_path,
+                    include_docstrings,
+                )?;
+                for comment in &mut detected {
+                    comment.set_metadata("edit", &(index + 1).to_string());
+                }
+                suppression_filter.extend(&detected);
+                let edit_comments = detect_new_comments_for_edit(
+                    &detector,
+                    old_string,
+                    detected,
+                    &file_path,
+                    include_docstrings,
+                    trace,
+                )?;
                 all_comments.extend(edit_comments);
             }
             all_comments
@@ -154,21 +407,306 @@ fn run() -> Result<u8> {
                 tracing::warn!("Skipping: No content to check");
                 return Ok(EXIT_PASS);
             }
-            detector.detect(&content, &file_path, true)
+            let detected = detector.detect(&content, &file_path, include_docstrings)?;
+            suppression_filter.extend(&detected);
+            for comment in &detected {
+                trace.capture(comment);
+            }
+            detected
         }
     };
-    if comments.is_empty() {
-        tracing::info!("success: no problematic comments/docstrings found");
-        return Ok(EXIT_PASS);
+    let missing_license = if tool_name == "Write" {
+        LicenseCheck::new(&config.license)
+            .stage(Stage::Config)?
+            .missing_header(&comments, &file_path)
+    } else {
+        None
+    };
+    let mut findings = suppression_filter
+        .expired()
+        .iter()
+        .filter(|c| {
+            comments
+                .iter()
+                .any(|n| n.location_key() == c.location_key())
+        })
+        .map(|c| {
+            let mut c = c.clone();
+            tag_comment(&mut c, &EXPIRED_SUPPRESSION);
+            trace.step(&c, "suppression marker is not honoured: expired or invalid");
+            Finding::new(c, config.severity.comment)
+        })
+        .collect::<Vec<Finding>>();
+    let relative_path = loaded.relative_path(&resolved_path);
+    let baseline =
+        Baseline::load(&loaded.root().join(&config.baseline.file)).stage(Stage::Config)?;
+    let comments = if config.baseline.enabled {
+        let mut matcher = baseline.matcher(&relative_path);
+        let comments = comments
+            .into_iter()
+            .filter(|c| {
+                let baselined = matcher.consume(c);
+                if baselined {
+                    trace.step(c, "skipped by baseline: matches an accepted comment");
+                } else {
+                    trace.step(c, "passed baseline");
+                }
+                !baselined
+            })
+            .collect::<Vec<CommentInfo>>();
+        if tool_name == "Write" {
+            for entry in matcher.stale() {
+                tracing::info!(
+                    "stale baseline entry {} for {}: {}",
+                    entry.fingerprint(),
+                    entry.path(),
+                    entry.text()
+                );
+            }
+        }
+        comments
+    } else {
+        comments
+    };
+    let rule_engine = RuleEngine::new(&config.rules, loaded.root()).stage(Stage::Config)?;
+    if let Some(comment) = missing_license {
+        trace.note("missing required license header");
+        findings.push(Finding::new(comment, config.severity.comment));
+    }
+    let mut unmatched = Vec::new();
+    for mut comment in comments {
+        if suppression_filter.should_skip(&comment) {
+            unmatched.push(comment);
+            continue;
+        }
+        let Some(rule) = rule_engine.evaluate(&comment, &relative_path) else {
+            if !rule_engine.rules().is_empty() {
+                trace.step(&comment, "no rule matched");
+            }
+            unmatched.push(comment);
+            continue;
+        };
+        match rule.action().severity() {
+            Some(severity) => {
+                trace.step(
+                    &comment,
+                    format!("matched rule {} ({})", rule.id(), rule.action().as_str()),
+                );
+                tag_comment(&mut comment, &CUSTOM_RULE);
+                comment.set_metadata("rule", rule.id());
+                if let Some(message) = rule.message() {
+                    comment.set_metadata("message", message);
+                }
+                findings.push(Finding::new(comment, severity));
+            }
+            None => {
+                tracing::debug!(
+                    "line {} allowed by rule {}",
+                    comment.line_number(),
+                    rule.id()
+                );
+                trace.step(&comment, format!("skipped by rule {}", rule.id()));
+            }
+        }
+    }
+    let packs = load_packs(loaded.root(), &config.packs).stage(Stage::Config)?;
+    let agent_memo_filter = AgentMemoFilter::with_patterns(&config.filters.agent_memo_patterns)
+        .stage(Stage::Config)?
+        .with_packs(&packs);
+    let filter_chain = FilterChain::from_config(
+        &config,
+        &packs,
+        &policy,
+        &agent_memo_filter,
+        &suppression_filter,
+    )
+    .stage(Stage::Config)?;
+    if trace.is_enabled() {
+        for comment in &unmatched {
+            trace.filters(comment, &filter_chain.evaluate(comment));
+        }
+    }
+    let filtered = filter_chain.apply(unmatched);
+    findings.extend(filtered.into_iter().map(|mut c| {
+        if let Some(pack) = agent_memo_filter.matching_pack(&c) {
+            c.set_metadata("pack", pack);
+        }
+        let is_agent_memo = agent_memo_filter.is_agent_memo(&c);
+        let rule = classify(&c, is_agent_memo);
+        tag_comment(&mut c, rule);
+        let severity = policy.severity(&c, is_agent_memo);
+        Finding::new(c, severity)
+    }));
+    let budget = Budget::new(&config.budget);
+    if budget.is_enabled() {
+        let before = findings
+            .iter()
+            .map(|f| f.comment().clone())
+            .collect::<Vec<CommentInfo>>();
+        let stats = detector.line_stats(&written_content, &file_path)?;
+        findings = budget.apply(findings, stats, baseline.density(&relative_path));
+        for comment in &before {
+            let kept = findings
+                .iter()
+                .any(|f| f.comment().location_key() == comment.location_key());
+            if !kept {
+                trace.step(comment, "skipped by budget: within budget");
+            }
+        }
+    }
+    findings.sort_by_key(|f| f.comment().line_number());
+    for finding in &findings {
+        trace.step(
+            finding.comment(),
+            format!(
+                "reported as {} {} ({})",
+                finding.comment().metadata("rule_id").unwrap_or_default(),
+                finding.comment().metadata("rule").unwrap_or_default(),
+                finding.severity().as_str()
+            ),
+        );
     }
-    let filtered = apply_filters(comments);
-    if filtered.is_empty() {
-        tracing::info!("success: no problematic comments/docstrings found");
+    for finding in &findings {
+        tracing::debug!(
+            "{} {} line {}: {}",
+            finding.severity().as_str(),
+            finding.comment().metadata("rule_id").unwrap_or_default(),
+            finding.comment().line_number(),
+            finding.comment().text().trim()
+        );
+    }
+    let prompt = cli.prompt.as_deref().or(config.message.prompt.as_deref());
+    let decision = decide(&findings, prompt, &agent_memo_filter);
+    trace.set_outcome(format!(
+        "decision: {} (exit {})",
+        decision.as_str(),
+        decision.exit_code()
+    ));
+    if cli.shadow || config.shadow.enabled {
+        let log_path = loaded.root().join(&config.shadow.log);
+        let record = ShadowRecord::new(
+            hook_input.session_id.as_deref(),
+            tool_name,
+            &relative_path.to_string_lossy(),
+            decision.as_str(),
+            decision.exit_code(),
+            &findings,
+            decision.message(),
+        );
+        if let Err(e) = append_shadow_record(&log_path, &record) {
+            tracing::warn!("failed to write shadow log {}: {:#}", log_path.display(), e);
+        }
+        tracing::info!(
+            "shadow: would have exited with {} ({})",
+            decision.exit_code(),
+            decision.as_str()
+        );
+        trace.set_outcome(format!(
+            "decision: {} (shadow mode, exit {})",
+            decision.as_str(),
+            EXIT_PASS
+        ));
         return Ok(EXIT_PASS);
     }
-    let message = format_hook_message(&filtered, cli.prompt.as_deref());
-    eprint!("{}", message);
-    Ok(EXIT_BLOCK)
+    match decision {
+        Decision::Pass => {
+            tracing::info!(
+                "success: no problematic comments/docstrings found (profile: {})",
+                policy.profile()
+            );
+            Ok(EXIT_PASS)
+        }
+        Decision::Warn(context) | Decision::Info(context) => {
+            let event_name = hook_input
+                .hook_event_name
+                .as_deref()
+                .unwrap_or("PostToolUse");
+            println!("{}", format_additional_context(event_name, &context));
+            Ok(EXIT_PASS)
+        }
+        Decision::Block(message) => {
+            eprint!("{}", message);
+            Ok(EXIT_BLOCK)
+        }
+    }
+}
+
+enum Decision {
+    Pass,
+    Info(String),
+    Warn(String),
+    Block(String),
+}
+
+impl Decision {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Decision::Pass => "pass",
+            Decision::Info(_) => "info",
+            Decision::Warn(_) => "warn",
+            Decision::Block(_) => "block",
+        }
+    }
+
+    fn exit_code(&self) -> u8 {
+        match self {
+            Decision::Block(_) => EXIT_BLOCK,
+            _ => EXIT_PASS,
+        }
+    }
+
+    fn message(&self) -> Option<&str> {
+        match self {
+            Decision::Pass => None,
+            Decision::Info(message) | Decision::Warn(message) | Decision::Block(message) => {
+                Some(message)
+            }
+        }
+    }
+}
+
+fn decide(
+    findings: &[Finding],
+    prompt: Option<&str>,
+    agent_memo_filter: &AgentMemoFilter,
+) -> Decision {
+    let (license, findings): (Vec<&Finding>, Vec<&Finding>) = findings
+        .iter()
+        .partition(|f| f.comment().metadata("rule_id") == Some(MISSING_LICENSE_HEADER.code()));
+    let mut sections = Vec::new();
+    let mut highest = None;
+    for severity in [Severity::Block, Severity::Warn, Severity::Info] {
+        let comments = comments_with_severity(&findings, severity);
+        let license_comments = comments_with_severity(&license, severity);
+        if !comments.is_empty() {
+            sections.push(match severity {
+                Severity::Block => format_hook_message(&comments, prompt, agent_memo_filter),
+                Severity::Warn => format_warning_message(&comments),
+                Severity::Info => format_info_message(&comments),
+            });
+        }
+        if !license_comments.is_empty() {
+            sections.push(format_license_message(&license_comments));
+        }
+        if highest.is_none() && !(comments.is_empty() && license_comments.is_empty()) {
+            highest = Some(severity);
+        }
+    }
+    let message = sections.join("\n---\n\n");
+    match highest {
+        Some(Severity::Block) => Decision::Block(message),
+        Some(Severity::Warn) => Decision::Warn(message),
+        Some(Severity::Info) => Decision::Info(message),
+        None => Decision::Pass,
+    }
+}
+
+fn comments_with_severity(findings: &[&Finding], severity: Severity) -> Vec<CommentInfo> {
+    findings
+        .iter()
+        .filter(|f| f.severity() == severity)
+        .map(|f| f.comment().clone())
+        .collect()
 }
 
 fn get_content_to_check(input: &HookInput) -> String {
@@ -200,16 +738,15 @@ fn get_content_to_check(input: &HookInput) -> String {
     }
 }
 
-fn apply_filters(comments: Vec<CommentInfo>) -> Vec<CommentInfo> {
-    let bdd_filter = BddFilter::new();
-    let directive_filter = DirectiveFilter::new();
-    let shebang_filter = ShebangFilter::new();
-    comments
-        .into_iter()
-        .filter(|c| !bdd_filter.should_skip(c))
-        .filter(|c| !directive_filter.should_skip(c))
-        .filter(|c| !shebang_filter.should_skip(c))
-        .collect()
+fn resolve_path(file_path: &str, cwd: Option<&str>) -> PathBuf {
+    let path = Path::new(file_path);
+    if path.is_absolute() {
+        return path.to_path_buf();
+    }
+    match cwd {
+        Some(cwd) if !cwd.is_empty() => Path::new(cwd).join(path),
+        _ => path.to_path_buf(),
+    }
 }
 
 fn build_comment_text_set(comments: &[CommentInfo]) -> HashSet<String> {
@@ -219,6 +756,7 @@ fn build_comment_text_set(comments: &[CommentInfo]) -> HashSet<String> {
 fn filter_new_comments(
     old_comments: &[CommentInfo],
     new_comments: Vec<CommentInfo>,
+    trace: &mut ExplainTrace,
 ) -> Vec<CommentInfo> {
     if old_comments.is_empty() {
         return new_comments;
@@ -226,17 +764,85 @@ fn filter_new_comments(
     let old_set = build_comment_text_set(old_comments);
     new_comments
         .into_iter()
-        .filter(|c| !old_set.contains(&c.normalised_text()))
+        .filter(|c| {
+            let existing = old_set.contains(&c.normalised_text());
+            if existing {
+                trace.step(c, "skipped: already present in old_string");
+            }
+            !existing
+        })
         .collect()
 }
 
 fn detect_new_comments_for_edit(
     detector: &CommentDetector,
     old_string: &str,
-    new_string: &str,
+    new_comments: Vec<CommentInfo>,
     file_path: &str,
-) -> Vec<CommentInfo> {
-    let old_comments = detector.detect(old_string, file_path, true);
-    let new_comments = detector.detect(new_string, file_path, true);
-    filter_new_comments(&old_comments, new_comments)
+    include_docstrings: bool,
+    trace: &mut ExplainTrace,
+) -> Result<Vec<CommentInfo>> {
+    for comment in &new_comments {
+        trace.capture(comment);
+    }
+    let old_comments = detector.detect(old_string, file_path, include_docstrings)?;
+    Ok(filter_new_comments(&old_comments, new_comments, trace))
+}
+
+#[cfg(test)]
+mod tests {
+    use claude_decomment_hook::filters::AgentMemoFilter;
+    use claude_decomment_hook::models::{CommentInfo, CommentType, Finding, Severity};
+
+    use super::{Decision, decide};
+
+    fn finding(text: &str, severity: Severity) -> Finding {
+        let comment = CommentInfo::new(
+            text.to_owned(),
+            1,
+            "app.py".to_owned(),
+            CommentType::Line,
+            false,
+        );
+        Finding::new(comment, severity)
+    }
+
+    fn decision(findings: &[Finding]) -> Decision {
+        decide(findings, None, &AgentMemoFilter::new())
+    }
+
+    #[test]
+    fn no_findings_pass() {
+        assert_eq!(decision(&[]).as_str(), "pass");
+    }
+
+    #[test]
+    fn info_findings_reach_the_agent_without_blocking() {
+        let decision = decision(&[finding("# noted", Severity::Info)]);
+        assert_eq!(decision.as_str(), "info");
+        assert_eq!(decision.exit_code(), 0);
+        assert!(decision.message().unwrap().contains("# noted"));
+    }
+
+    #[test]
+    fn warnings_outrank_info() {
+        let decision = decision(&[
+            finding("# noted", Severity::Info),
+            finding("# discouraged", Severity::Warn),
+        ]);
+        assert_eq!(decision.as_str(), "warn");
+        let message = decision.message().unwrap();
+        assert!(message.contains("# noted") && message.contains("# discouraged"));
+    }
+
+    #[test]
+    fn blocking_findings_block_and_keep_the_rest() {
+        let decision = decision(&[
+            finding("# noted", Severity::Info),
+            finding("# removed", Severity::Block),
+        ]);
+        assert_eq!(decision.as_str(), "block");
+        assert_eq!(decision.exit_code(), 2);
+        assert!(decision.message().unwrap().contains("# noted"));
+    }
 }
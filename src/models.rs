@@ -1,13 +1,89 @@
 use std::collections::HashMap;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
 pub enum CommentType {
     Line,
     Block,
     Docstring,
+    OuterDoc,
+    InnerDoc,
+    Directive,
+    LicenseHeader,
+    Todo,
+    CommentedOutCode,
+    Shebang,
+    Banner,
+    Safety,
+    Annotation,
+}
+
+impl CommentType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CommentType::Line => "line",
+            CommentType::Block => "block",
+            CommentType::Docstring => "docstring",
+            CommentType::OuterDoc => "outer-doc",
+            CommentType::InnerDoc => "inner-doc",
+            CommentType::Directive => "directive",
+            CommentType::LicenseHeader => "license-header",
+            CommentType::Todo => "todo",
+            CommentType::CommentedOutCode => "commented-out-code",
+            CommentType::Shebang => "shebang",
+            CommentType::Banner => "banner",
+            CommentType::Safety => "safety",
+            CommentType::Annotation => "annotation",
+        }
+    }
+
+    pub fn is_documentation(&self) -> bool {
+        matches!(
+            self,
+            CommentType::Docstring | CommentType::OuterDoc | CommentType::InnerDoc
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum CommentPosition {
+    Leading,
+    Trailing,
+    FileHeader,
+}
+
+impl CommentPosition {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CommentPosition::Leading => "leading",
+            CommentPosition::Trailing => "trailing",
+            CommentPosition::FileHeader => "file-header",
+        }
+    }
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warn,
+    Block,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Warn => "warn",
+            Severity::Block => "block",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -51,7 +127,96 @@ impl CommentInfo {
         &self.file_path
     }
 
+    pub fn comment_type(&self) -> CommentType {
+        self.comment_type
+    }
+
+    pub fn is_docstring(&self) -> bool {
+        self.is_docstring
+    }
+
+    pub fn set_comment_type(&mut self, comment_type: CommentType) {
+        self.comment_type = comment_type;
+    }
+
+    pub fn language(&self) -> Option<&str> {
+        self.metadata("language")
+    }
+
+    pub fn symbol(&self) -> Option<&str> {
+        self.metadata("symbol")
+    }
+
+    pub fn edit(&self) -> Option<usize> {
+        self.metadata("edit").and_then(|e| e.parse().ok())
+    }
+
+    pub fn location_key(&self) -> (Option<usize>, usize, String) {
+        (self.edit(), self.line_number, self.text.clone())
+    }
+
+    pub fn metadata(&self, key: &str) -> Option<&str> {
+        self.metadata.as_ref()?.get(key).map(String::as_str)
+    }
+
+    pub fn set_metadata(&mut self, key: &str, value: &str) {
+        self.metadata
+            .get_or_insert_with(HashMap::new)
+            .insert(key.to_owned(), value.to_owned());
+    }
+
     pub fn normalised_text(&self) -> String {
         self.text.trim().to_lowercase()
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineStats {
+    comment_lines: usize,
+    code_lines: usize,
+}
+
+impl LineStats {
+    pub fn new(comment_lines: usize, code_lines: usize) -> Self {
+        Self {
+            comment_lines,
+            code_lines,
+        }
+    }
+
+    pub fn comment_lines(&self) -> usize {
+        self.comment_lines
+    }
+
+    pub fn code_lines(&self) -> usize {
+        self.code_lines
+    }
+
+    pub fn density(&self) -> f64 {
+        self.comment_lines as f64 / self.code_lines.max(1) as f64
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Finding {
+    comment: CommentInfo,
+    severity: Severity,
+}
+
+impl Finding {
+    pub fn new(comment: CommentInfo, severity: Severity) -> Self {
+        Self { comment, severity }
+    }
+
+    pub fn comment(&self) -> &CommentInfo {
+        &self.comment
+    }
+
+    pub fn comment_mut(&mut self) -> &mut CommentInfo {
+        &mut self.comment
+    }
+
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+}
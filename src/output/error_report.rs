@@ -0,0 +1,27 @@
+use serde_json::json;
+
+use crate::error::{FailMode, Stage};
+
+pub fn format_error_report(stage: Stage, error: &anyhow::Error, fail_mode: FailMode) -> String {
+    json!({
+        "decomment_error": {
+            "stage": stage,
+            "error": format!("{:#}", error),
+            "on_error": fail_mode.as_str(),
+        }
+    })
+    .to_string()
+}
+
+pub fn format_error_message(stage: Stage, error: &anyhow::Error) -> String {
+    let mut sb = String::new();
+    sb.push_str("COMMENT CHECK FAILED - TOOL CALL BLOCKED\n\n");
+    sb.push_str(&format!(
+        "The comment checker could not complete the {} stage, and this project is configured to fail closed.\n",
+        stage
+    ));
+    sb.push_str(&format!("Error: {:#}\n\n", error));
+    sb.push_str("Tell the user that the comment checker failed and show them the error above.\n");
+    sb.push_str("Do not try to work around this hook.\n\n");
+    sb
+}
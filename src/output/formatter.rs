@@ -1,39 +1,24 @@
 use std::collections::HashMap;
 
+use crate::catalog::{AGENT_MEMO, MISSING_LICENSE_HEADER};
 use crate::filters::AgentMemoFilter;
 use crate::models::CommentInfo;
 use crate::output::xml_builder::build_comments_xml;
 
-pub fn format_hook_message(comments: &[CommentInfo], custom_prompt: Option<&str>) -> String {
+pub fn format_hook_message(
+    comments: &[CommentInfo],
+    custom_prompt: Option<&str>,
+    agent_memo_filter: &AgentMemoFilter,
+) -> String {
     if comments.is_empty() {
         return String::new();
     }
-    let mut by_file = HashMap::<&str, Vec<&CommentInfo>>::new();
-    let mut file_order = Vec::<&str>::new();
-    for comment in comments {
-        let path = comment.file_path();
-        if !by_file.contains_key(path) {
-            file_order.push(path);
-        }
-        by_file.entry(path).or_default().push(comment);
-    }
-    let mut comments_xml = String::new();
-    for file_path in &file_order {
-        if let Some(file_comments) = by_file.get(file_path) {
-            let owned_comments = file_comments
-                .iter()
-                .map(|c| (*c).clone())
-                .collect::<Vec<CommentInfo>>();
-            comments_xml.push_str(&build_comments_xml(&owned_comments, file_path));
-            comments_xml.push('\n');
-        }
-    }
+    let comments_xml = build_grouped_comments_xml(comments);
     if let Some(prompt) = custom_prompt
         && !prompt.is_empty()
     {
         return prompt.replace("{{comments}}", &comments_xml);
     }
-    let agent_memo_filter = AgentMemoFilter::new();
     let agent_memo_comments = comments
         .iter()
         .filter(|c| agent_memo_filter.is_agent_memo(c))
@@ -71,8 +56,9 @@ pub fn format_hook_message(comments: &[CommentInfo], custom_prompt: Option<&str>
         sb.push_str("Detected agent memo comments:\n");
         for memo in &agent_memo_comments {
             sb.push_str(&format!(
-                "  - Line {}: {}\n",
+                "  - Line {} [{}]: {}\n",
                 memo.line_number(),
+                memo.metadata("rule_id").unwrap_or(AGENT_MEMO.code()),
                 memo.text().trim()
             ));
         }
@@ -109,7 +95,85 @@ pub fn format_hook_message(comments: &[CommentInfo], custom_prompt: Option<&str>
     sb.push_str("MANDATORY REQUIREMENT: You must acknowledge this hook message and take one of the above actions.\n");
     sb.push_str("Review in the above priority order and take the corresponding action EVERY TIME this appears.\n\n");
     sb.push_str("REMINDER: These rules apply to ALL your future code, not just this specific edit. Always be deliberate and cautious when writing comments - only add them when absolutely necessary.\n\n");
+    sb.push_str("Each comment below carries a rule-id; run `explain <rule-id>` for details.\n");
     sb.push_str("Detected comments/docstrings:\n");
     sb.push_str(&comments_xml);
     sb
 }
+
+pub fn format_warning_message(comments: &[CommentInfo]) -> String {
+    if comments.is_empty() {
+        return String::new();
+    }
+    let mut sb = String::new();
+    sb.push_str("COMMENT/DOCSTRING NOTICE - NO ACTION REQUIRED\n\n");
+    sb.push_str("Your recent changes contain comments or docstrings that this project allows but discourages.\n");
+    sb.push_str(
+        "Keep them only if they explain something the code cannot; otherwise remove them.\n\n",
+    );
+    sb.push_str("Detected comments/docstrings:\n");
+    sb.push_str(&build_grouped_comments_xml(comments));
+    sb
+}
+
+pub fn format_info_message(comments: &[CommentInfo]) -> String {
+    if comments.is_empty() {
+        return String::new();
+    }
+    let mut sb = String::new();
+    sb.push_str("COMMENT/DOCSTRING INFO - FOR REFERENCE ONLY\n\n");
+    sb.push_str("Your recent changes contain comments or docstrings that this project records for information.\n");
+    sb.push_str("No change is needed; mention them to the user only if relevant.\n\n");
+    sb.push_str("Detected comments/docstrings:\n");
+    sb.push_str(&build_grouped_comments_xml(comments));
+    sb
+}
+
+pub fn format_license_message(comments: &[CommentInfo]) -> String {
+    if comments.is_empty() {
+        return String::new();
+    }
+    let mut sb = String::new();
+    sb.push_str("LICENSE HEADER MISSING - ADD ONE TO THE FILE\n\n");
+    sb.push_str("This project requires every new file to start with a license header.\n");
+    sb.push_str("\t-> Add the project's license header at the very top of the file.\n");
+    sb.push_str(
+        "\t-> Do not remove any other comments to resolve this; only the header is missing.\n\n",
+    );
+    sb.push_str("Files without the required header:\n");
+    for comment in comments {
+        sb.push_str(&format!(
+            "  - {} [{}]: {}\n",
+            comment.file_path(),
+            comment
+                .metadata("rule_id")
+                .unwrap_or(MISSING_LICENSE_HEADER.code()),
+            comment.metadata("message").unwrap_or_default()
+        ));
+    }
+    sb
+}
+
+fn build_grouped_comments_xml(comments: &[CommentInfo]) -> String {
+    let mut by_file = HashMap::<&str, Vec<&CommentInfo>>::new();
+    let mut file_order = Vec::<&str>::new();
+    for comment in comments {
+        let path = comment.file_path();
+        if !by_file.contains_key(path) {
+            file_order.push(path);
+        }
+        by_file.entry(path).or_default().push(comment);
+    }
+    let mut comments_xml = String::new();
+    for file_path in &file_order {
+        if let Some(file_comments) = by_file.get(file_path) {
+            let owned_comments = file_comments
+                .iter()
+                .map(|c| (*c).clone())
+                .collect::<Vec<CommentInfo>>();
+            comments_xml.push_str(&build_comments_xml(&owned_comments, file_path));
+            comments_xml.push('\n');
+        }
+    }
+    comments_xml
+}
@@ -0,0 +1,11 @@
+use serde_json::json;
+
+pub fn format_additional_context(event_name: &str, context: &str) -> String {
+    json!({
+        "hookSpecificOutput": {
+            "hookEventName": event_name,
+            "additionalContext": context,
+        }
+    })
+    .to_string()
+}
@@ -1,4 +1,14 @@
+mod error_report;
 mod formatter;
+mod hook_output;
+mod shadow_log;
+mod trace;
 mod xml_builder;
 
-pub use formatter::format_hook_message;
+pub use error_report::{format_error_message, format_error_report};
+pub use formatter::{
+    format_hook_message, format_info_message, format_license_message, format_warning_message,
+};
+pub use hook_output::format_additional_context;
+pub use shadow_log::{ShadowRecord, append_shadow_record};
+pub use trace::ExplainTrace;
@@ -0,0 +1,69 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use jiff::Timestamp;
+use serde::Serialize;
+
+use crate::models::Finding;
+
+#[derive(Debug, Serialize)]
+pub struct ShadowRecord {
+    timestamp: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    session_id: Option<String>,
+    tool_name: String,
+    file_path: String,
+    decision: String,
+    exit_code: u8,
+    findings: usize,
+    rules: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+}
+
+impl ShadowRecord {
+    pub fn new(
+        session_id: Option<&str>,
+        tool_name: &str,
+        file_path: &str,
+        decision: &str,
+        exit_code: u8,
+        findings: &[Finding],
+        message: Option<&str>,
+    ) -> Self {
+        let mut rules = findings
+            .iter()
+            .filter_map(|f| f.comment().metadata("rule_id"))
+            .map(str::to_owned)
+            .collect::<Vec<String>>();
+        rules.sort();
+        rules.dedup();
+        Self {
+            timestamp: Timestamp::now().to_string(),
+            session_id: session_id.map(str::to_owned),
+            tool_name: tool_name.to_owned(),
+            file_path: file_path.to_owned(),
+            decision: decision.to_owned(),
+            exit_code,
+            findings: findings.len(),
+            rules,
+            message: message.map(str::to_owned),
+        }
+    }
+}
+
+pub fn append_shadow_record(path: &Path, record: &ShadowRecord) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("failed to open {}", path.display()))?;
+    let line = serde_json::to_string(record).context("failed to serialise shadow record")?;
+    writeln!(file, "{}", line).with_context(|| format!("failed to write {}", path.display()))
+}
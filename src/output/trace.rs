@@ -0,0 +1,132 @@
+use std::collections::BTreeMap;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use crate::filters::{FilterDecision, FilterTrace};
+use crate::models::CommentInfo;
+
+pub struct ExplainTrace {
+    enabled: bool,
+    file_path: Option<String>,
+    notes: Vec<String>,
+    comments: BTreeMap<(Option<usize>, usize, String), Vec<String>>,
+    outcome: Option<String>,
+}
+
+impl ExplainTrace {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            file_path: None,
+            notes: Vec::new(),
+            comments: BTreeMap::new(),
+            outcome: None,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_file_path(&mut self, file_path: &str) {
+        self.file_path = Some(file_path.to_owned());
+    }
+
+    pub fn note(&mut self, note: impl Into<String>) {
+        if self.enabled {
+            self.notes.push(note.into());
+        }
+    }
+
+    pub fn set_outcome(&mut self, outcome: impl Into<String>) {
+        if self.enabled {
+            self.outcome = Some(outcome.into());
+        }
+    }
+
+    pub fn capture(&mut self, comment: &CommentInfo) {
+        if !self.enabled {
+            return;
+        }
+        let mut step = format!(
+            "captured by `{}` as {}",
+            comment.metadata("query").unwrap_or("(unknown query)"),
+            comment.comment_type().as_str()
+        );
+        if let Some(kind) = comment.metadata("node_kind") {
+            step.push_str(&format!(" ({}", kind));
+            if let Some(position) = comment.metadata("position") {
+                step.push_str(&format!(", {}", position));
+            }
+            step.push(')');
+        }
+        self.step(comment, step);
+    }
+
+    pub fn step(&mut self, comment: &CommentInfo, step: impl Into<String>) {
+        if self.enabled {
+            self.comments
+                .entry(comment.location_key())
+                .or_default()
+                .push(step.into());
+        }
+    }
+
+    pub fn filters(&mut self, comment: &CommentInfo, trace: &[FilterTrace]) {
+        for entry in trace {
+            let step = match (entry.decision(), entry.decision().reason()) {
+                (FilterDecision::Drop(_), Some(reason)) => {
+                    format!("skipped by {}: {}", entry.filter(), reason)
+                }
+                (_, Some(reason)) => format!("kept by {}: {}", entry.filter(), reason),
+                (_, None) => format!("passed {}", entry.filter()),
+            };
+            self.step(comment, step);
+        }
+    }
+
+    pub fn write(&self, target: Option<&Path>) -> Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+        let mut out = format!(
+            "explain {}\n",
+            self.file_path.as_deref().unwrap_or("(no file path)")
+        );
+        for note in &self.notes {
+            out.push_str(&format!("  {}\n", note));
+        }
+        if self.comments.is_empty() {
+            out.push_str("  no comments captured\n");
+        }
+        for ((edit, line, text), steps) in &self.comments {
+            let first_line = text.lines().next().unwrap_or_default().trim();
+            match edit {
+                Some(edit) => {
+                    out.push_str(&format!("  edit {} line {}: {}\n", edit, line, first_line))
+                }
+                None => out.push_str(&format!("  line {}: {}\n", line, first_line)),
+            }
+            for step in steps {
+                out.push_str(&format!("    {}\n", step));
+            }
+        }
+        if let Some(outcome) = &self.outcome {
+            out.push_str(&format!("  {}\n", outcome));
+        }
+        match target {
+            Some(path) => OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .and_then(|mut f| f.write_all(out.as_bytes()))
+                .with_context(|| format!("failed to write explain trace to {}", path.display())),
+            None => io::stderr()
+                .write_all(out.as_bytes())
+                .context("failed to write explain trace"),
+        }
+    }
+}
@@ -7,9 +7,30 @@ pub fn build_comments_xml(comments: &[CommentInfo], file_path: &str) -> String {
     let mut sb = String::new();
     sb.push_str(&format!("<comments file=\"{}\">\n", file_path));
     for comment in comments {
+        let pack = comment
+            .metadata("pack")
+            .map(|p| format!(" pack=\"{}\"", p))
+            .unwrap_or_default();
+        let rule_id = comment
+            .metadata("rule_id")
+            .map(|r| format!(" rule-id=\"{}\"", r))
+            .unwrap_or_default();
+        let rule = comment
+            .metadata("rule")
+            .map(|r| format!(" rule=\"{}\"", r))
+            .unwrap_or_default();
+        let message = comment
+            .metadata("message")
+            .map(|m| format!(" message=\"{}\"", m.replace('"', "&quot;")))
+            .unwrap_or_default();
         sb.push_str(&format!(
-            "\t<comment line-number=\"{}\">{}</comment>\n",
+            "\t<comment line-number=\"{}\" type=\"{}\"{}{}{}{}>{}</comment>\n",
             comment.line_number(),
+            comment.comment_type().as_str(),
+            rule_id,
+            rule,
+            pack,
+            message,
             comment.text()
         ));
     }
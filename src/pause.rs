@@ -0,0 +1,103 @@
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use jiff::Timestamp;
+use serde::Deserialize;
+
+pub const DISABLE_ENV: &str = "DECOMMENT_DISABLE";
+pub const PAUSE_FILE: &str = ".decomment/pause";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PauseReason {
+    Environment(String),
+    PauseFile {
+        path: PathBuf,
+        until: Option<Timestamp>,
+    },
+}
+
+impl fmt::Display for PauseReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PauseReason::Environment(scope) => write!(f, "{}={}", DISABLE_ENV, scope),
+            PauseReason::PauseFile {
+                path,
+                until: Some(until),
+            } => write!(f, "{} until {}", path.display(), until),
+            PauseReason::PauseFile { path, until: None } => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct PauseFile {
+    until: Option<Timestamp>,
+    sessions: Vec<String>,
+}
+
+pub fn check_pause(file_path: &Path, session_id: Option<&str>) -> Option<PauseReason> {
+    if let Ok(value) = env::var(DISABLE_ENV)
+        && let Some(scope) = env_scope_matches(&value, file_path, session_id)
+    {
+        return Some(PauseReason::Environment(scope));
+    }
+    let start = file_path.parent()?;
+    for dir in start.ancestors() {
+        let path = dir.join(PAUSE_FILE);
+        if path.is_file() {
+            return pause_file_matches(&path, session_id);
+        }
+    }
+    None
+}
+
+fn env_scope_matches(value: &str, file_path: &Path, session_id: Option<&str>) -> Option<String> {
+    for scope in value.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        let matched = match scope.split_once(':') {
+            Some(("session", id)) => session_id == Some(id),
+            Some(("project", dir)) => file_path.starts_with(dir),
+            _ => matches!(
+                scope.to_lowercase().as_str(),
+                "1" | "true" | "yes" | "on" | "all"
+            ),
+        };
+        if matched {
+            return Some(scope.to_owned());
+        }
+    }
+    None
+}
+
+fn pause_file_matches(path: &Path, session_id: Option<&str>) -> Option<PauseReason> {
+    let pause = match read_pause_file(path) {
+        Ok(pause) => pause,
+        Err(e) => {
+            tracing::warn!("ignoring unreadable pause file {}: {:#}", path.display(), e);
+            return None;
+        }
+    };
+    if let Some(until) = pause.until
+        && until <= Timestamp::now()
+    {
+        tracing::debug!("pause file {} expired at {}", path.display(), until);
+        return None;
+    }
+    if !pause.sessions.is_empty()
+        && !session_id.is_some_and(|id| pause.sessions.iter().any(|s| s == id))
+    {
+        return None;
+    }
+    Some(PauseReason::PauseFile {
+        path: path.to_path_buf(),
+        until: pause.until,
+    })
+}
+
+fn read_pause_file(path: &Path) -> Result<PauseFile> {
+    let content = fs::read_to_string(path).context("failed to read pause file")?;
+    toml::from_str(&content).context("failed to parse pause file")
+}
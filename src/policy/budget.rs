@@ -0,0 +1,97 @@
+use crate::catalog::{COMMENT_BUDGET, DOCSTRING, GENERIC_COMMENT, tag_comment};
+use crate::config::BudgetConfig;
+use crate::models::{Finding, LineStats};
+
+const DENSITY_PRECISION: f64 = 10_000.0;
+
+pub fn round_density(density: f64) -> f64 {
+    (density * DENSITY_PRECISION).round() / DENSITY_PRECISION
+}
+
+pub struct Budget<'a> {
+    config: &'a BudgetConfig,
+}
+
+impl<'a> Budget<'a> {
+    pub fn new(config: &'a BudgetConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.max_new_comments.is_some()
+            || self.config.max_density.is_some()
+            || self.config.ratchet
+    }
+
+    pub fn breach(
+        &self,
+        new_comments: usize,
+        stats: LineStats,
+        recorded_density: Option<f64>,
+    ) -> Option<String> {
+        if let Some(max) = self.config.max_new_comments
+            && new_comments > max
+        {
+            return Some(format!(
+                "{} new comments exceed the budget of {} per edit",
+                new_comments, max
+            ));
+        }
+        let density = round_density(stats.density());
+        if let Some(max) = self.config.max_density
+            && density > max
+        {
+            return Some(format!(
+                "comment density {:.2} exceeds the maximum of {:.2} ({} comment lines, {} code lines)",
+                density,
+                max,
+                stats.comment_lines(),
+                stats.code_lines()
+            ));
+        }
+        if self.config.ratchet
+            && let Some(recorded) = recorded_density
+            && density > recorded
+        {
+            return Some(format!(
+                "comment density {:.2} is above the recorded {:.2}",
+                density, recorded
+            ));
+        }
+        None
+    }
+
+    pub fn apply(
+        &self,
+        findings: Vec<Finding>,
+        stats: LineStats,
+        recorded_density: Option<f64>,
+    ) -> Vec<Finding> {
+        let is_budgeted = |f: &Finding| {
+            f.comment()
+                .metadata("rule_id")
+                .is_some_and(|id| id == GENERIC_COMMENT.code() || id == DOCSTRING.code())
+        };
+        let new_comments = findings.iter().filter(|f| is_budgeted(f)).count();
+        match self.breach(new_comments, stats, recorded_density) {
+            Some(message) => findings
+                .into_iter()
+                .map(|mut f| {
+                    if is_budgeted(&f) {
+                        tag_comment(f.comment_mut(), &COMMENT_BUDGET);
+                        f.comment_mut().set_metadata("message", &message);
+                    }
+                    f
+                })
+                .collect(),
+            None => {
+                tracing::info!(
+                    "{} new comments within budget (density {:.2})",
+                    new_comments,
+                    stats.density()
+                );
+                findings.into_iter().filter(|f| !is_budgeted(f)).collect()
+            }
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+
+use crate::catalog::{MISSING_LICENSE_HEADER, tag_comment};
+use crate::config::LicenseConfig;
+use crate::models::{CommentInfo, CommentPosition, CommentType};
+
+pub struct LicenseCheck<'a> {
+    config: &'a LicenseConfig,
+    pattern: Option<Regex>,
+}
+
+impl<'a> LicenseCheck<'a> {
+    pub fn new(config: &'a LicenseConfig) -> Result<Self> {
+        let pattern = config
+            .pattern
+            .as_deref()
+            .map(|p| Regex::new(p).with_context(|| format!("invalid license.pattern: {}", p)))
+            .transpose()?;
+        Ok(Self { config, pattern })
+    }
+
+    pub fn missing_header(&self, comments: &[CommentInfo], file_path: &str) -> Option<CommentInfo> {
+        if !self.config.require {
+            return None;
+        }
+        let satisfied = match &self.pattern {
+            Some(pattern) => comments.iter().any(|c| {
+                c.metadata("position") == Some(CommentPosition::FileHeader.as_str())
+                    && pattern.is_match(c.text())
+            }),
+            None => comments
+                .iter()
+                .any(|c| c.comment_type() == CommentType::LicenseHeader),
+        };
+        if satisfied {
+            return None;
+        }
+        let mut comment = CommentInfo::new(
+            String::new(),
+            1,
+            file_path.to_owned(),
+            CommentType::LicenseHeader,
+            false,
+        );
+        tag_comment(&mut comment, &MISSING_LICENSE_HEADER);
+        let message = match &self.pattern {
+            Some(pattern) => format!(
+                "file must start with a license header matching `{}`",
+                pattern.as_str()
+            ),
+            None => "file must start with a license header".to_owned(),
+        };
+        comment.set_metadata("message", &message);
+        Some(comment)
+    }
+}
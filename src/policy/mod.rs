@@ -0,0 +1,11 @@
+mod budget;
+mod license;
+mod profile;
+mod resolved;
+mod rules;
+
+pub use budget::{Budget, round_density};
+pub use license::LicenseCheck;
+pub use profile::Profile;
+pub use resolved::Policy;
+pub use rules::RuleEngine;
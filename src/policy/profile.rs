@@ -0,0 +1,48 @@
+use std::fmt;
+
+use clap::ValueEnum;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::models::CommentInfo;
+
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema, ValueEnum,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum Profile {
+    Strict,
+    #[default]
+    Balanced,
+    MemoOnly,
+    DocsAllowed,
+}
+
+impl Profile {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Profile::Strict => "strict",
+            Profile::Balanced => "balanced",
+            Profile::MemoOnly => "memo-only",
+            Profile::DocsAllowed => "docs-allowed",
+        }
+    }
+
+    pub fn allows_exemptions(&self) -> bool {
+        *self != Profile::Strict
+    }
+
+    pub fn blocks_only_agent_memos(&self) -> bool {
+        *self == Profile::MemoOnly
+    }
+
+    pub fn blocks(&self, comment: &CommentInfo) -> bool {
+        !(*self == Profile::DocsAllowed && comment.is_docstring())
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
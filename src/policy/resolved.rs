@@ -0,0 +1,137 @@
+use crate::config::{Config, DocPolicy, FilterName, LanguageConfig};
+use crate::filters::{Filter, FilterDecision};
+use crate::models::{CommentInfo, CommentType, Severity};
+use crate::policy::Profile;
+
+pub struct Policy<'a> {
+    config: &'a Config,
+    profile: Profile,
+}
+
+impl<'a> Policy<'a> {
+    pub fn new(config: &'a Config, profile: Profile) -> Self {
+        Self { config, profile }
+    }
+
+    pub fn profile(&self) -> Profile {
+        self.profile
+    }
+
+    pub fn include_docstrings(&self, language: Option<&str>) -> bool {
+        self.language(language)
+            .and_then(|l| l.docstrings)
+            .unwrap_or(self.config.include_docstrings)
+    }
+
+    pub fn detects_docstrings(&self, language: Option<&str>) -> bool {
+        self.include_docstrings(language)
+            || self.language(language).is_some_and(|l| {
+                [l.public_docs, l.private_docs, l.module_docs].contains(&Some(DocPolicy::Comment))
+            })
+    }
+
+    pub fn doc_policy(&self, comment: &CommentInfo) -> Option<DocPolicy> {
+        if !comment.is_docstring() {
+            return None;
+        }
+        let language = self.language(comment.language())?;
+        match comment.metadata("visibility")? {
+            "public" => language.public_docs,
+            "private" => language.private_docs,
+            "module" => language.module_docs,
+            _ => None,
+        }
+    }
+
+    pub fn blocks(&self, comment: &CommentInfo) -> bool {
+        if matches!(
+            comment.comment_type(),
+            CommentType::Safety | CommentType::Annotation
+        ) || comment.metadata("directive").is_some()
+        {
+            return false;
+        }
+        if comment.comment_type() == CommentType::LicenseHeader && self.config.license.allow {
+            return false;
+        }
+        match self.doc_policy(comment) {
+            Some(DocPolicy::Allow) => return false,
+            Some(DocPolicy::Comment) => {}
+            Some(DocPolicy::Docstring) | None => {
+                if !self.profile.blocks(comment) {
+                    return false;
+                }
+                if comment.is_docstring() && !self.include_docstrings(comment.language()) {
+                    return false;
+                }
+            }
+        }
+        match self
+            .language(comment.language())
+            .and_then(|l| l.block.as_ref())
+        {
+            Some(types) => types.contains(&comment.comment_type()),
+            None => true,
+        }
+    }
+
+    pub fn severity(&self, comment: &CommentInfo, is_agent_memo: bool) -> Severity {
+        let severity = &self.config.severity;
+        if is_agent_memo {
+            severity.agent_memo
+        } else if comment.is_docstring() && self.doc_policy(comment) != Some(DocPolicy::Comment) {
+            severity.docstring
+        } else {
+            severity.comment
+        }
+    }
+
+    pub fn filter_enabled(&self, language: Option<&str>, filter: FilterName) -> bool {
+        let filters = &self.config.filters;
+        let globally_enabled = match filter {
+            FilterName::Bdd => filters.bdd && self.profile.allows_exemptions(),
+            FilterName::Directive => filters.directive && self.profile.allows_exemptions(),
+            FilterName::Shebang => filters.shebang,
+            FilterName::Allow => true,
+        };
+        if !globally_enabled {
+            return false;
+        }
+        match self.language(language).and_then(|l| l.filters.as_ref()) {
+            Some(enabled) => enabled.contains(&filter),
+            None => true,
+        }
+    }
+
+    fn language(&self, language: Option<&str>) -> Option<&'a LanguageConfig> {
+        self.config.languages.get(language?)
+    }
+}
+
+impl Filter for Policy<'_> {
+    fn name(&self) -> &str {
+        "policy"
+    }
+
+    fn evaluate(&self, comment: &CommentInfo) -> FilterDecision {
+        if self.blocks(comment) {
+            FilterDecision::keep()
+        } else if comment.comment_type() == CommentType::LicenseHeader {
+            FilterDecision::drop("license header")
+        } else if comment.comment_type() == CommentType::Safety {
+            FilterDecision::drop("SAFETY justification for unsafe code")
+        } else if let Some(annotation) = comment.metadata("annotation") {
+            FilterDecision::drop(format!("{} annotation read by tools", annotation))
+        } else if self.doc_policy(comment) == Some(DocPolicy::Allow) {
+            FilterDecision::drop(format!(
+                "{} documentation is allowed",
+                comment.metadata("visibility").unwrap_or_default()
+            ))
+        } else {
+            FilterDecision::drop(format!(
+                "not reported under the {} profile and language settings",
+                self.profile
+            ))
+        }
+    }
+}
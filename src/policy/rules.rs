@@ -0,0 +1,132 @@
+use std::cmp::Reverse;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use regex::Regex;
+
+use crate::config::{PathMatcher, RuleAction, RuleConfig};
+use crate::models::{CommentInfo, CommentPosition, CommentType};
+
+pub struct Rule {
+    id: String,
+    pattern: Option<Regex>,
+    comment_types: Vec<CommentType>,
+    node_kinds: Vec<String>,
+    languages: Vec<String>,
+    paths: Option<PathMatcher>,
+    positions: Vec<CommentPosition>,
+    action: RuleAction,
+    message: Option<String>,
+}
+
+impl Rule {
+    fn from_config(index: usize, config: &RuleConfig, root: &Path) -> Result<Self> {
+        let id = config
+            .id
+            .clone()
+            .unwrap_or_else(|| format!("rule-{}", index + 1));
+        let pattern = config
+            .pattern
+            .as_deref()
+            .map(|p| Regex::new(p).with_context(|| format!("rule {}: invalid pattern: {}", id, p)))
+            .transpose()?;
+        let paths = if config.paths.is_empty() {
+            None
+        } else {
+            Some(
+                PathMatcher::new(root, &config.paths)
+                    .with_context(|| format!("rule {}: invalid paths", id))?,
+            )
+        };
+        Ok(Self {
+            id,
+            pattern,
+            comment_types: config.comment_types.clone(),
+            node_kinds: config.node_kinds.clone(),
+            languages: config.languages.clone(),
+            paths,
+            positions: config.positions.clone(),
+            action: config.action,
+            message: config.message.clone(),
+        })
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn action(&self) -> RuleAction {
+        self.action
+    }
+
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    fn matches(&self, comment: &CommentInfo, relative_path: &Path) -> bool {
+        if let Some(pattern) = &self.pattern
+            && !pattern.is_match(comment.text())
+        {
+            return false;
+        }
+        if !self.comment_types.is_empty() && !self.comment_types.contains(&comment.comment_type()) {
+            return false;
+        }
+        if !matches_any(&self.node_kinds, comment.metadata("node_kind")) {
+            return false;
+        }
+        if !matches_any(&self.languages, comment.language()) {
+            return false;
+        }
+        if !self.positions.is_empty()
+            && !self
+                .positions
+                .iter()
+                .any(|p| comment.metadata("position") == Some(p.as_str()))
+        {
+            return false;
+        }
+        match &self.paths {
+            Some(paths) => paths.is_match(relative_path),
+            None => true,
+        }
+    }
+}
+
+fn matches_any(values: &[String], actual: Option<&str>) -> bool {
+    values.is_empty() || actual.is_some_and(|a| values.iter().any(|v| v == a))
+}
+
+pub struct RuleEngine {
+    rules: Vec<Rule>,
+}
+
+impl RuleEngine {
+    pub fn new(configs: &[RuleConfig], root: &Path) -> Result<Self> {
+        let mut indexed = configs
+            .iter()
+            .enumerate()
+            .map(|(index, config)| {
+                Ok((
+                    config.priority,
+                    index,
+                    Rule::from_config(index, config, root)?,
+                ))
+            })
+            .collect::<Result<Vec<(i32, usize, Rule)>>>()?;
+        indexed.sort_by_key(|(priority, index, _)| Reverse((*priority, *index)));
+        Ok(Self {
+            rules: indexed.into_iter().map(|(_, _, rule)| rule).collect(),
+        })
+    }
+
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    pub fn evaluate(&self, comment: &CommentInfo, relative_path: &Path) -> Option<&Rule> {
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(comment, relative_path))
+    }
+}
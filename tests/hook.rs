@@ -0,0 +1,149 @@
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use serde_json::{Value, json};
+
+const EXIT_PASS: i32 = 0;
+const EXIT_BLOCK: i32 = 2;
+
+struct Project {
+    dir: PathBuf,
+}
+
+impl Project {
+    fn new(name: &str, config: &str) -> Self {
+        let dir =
+            std::env::temp_dir().join(format!("decomment-hook-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join(".decomment.toml"),
+            format!("root = true\n{}", config),
+        )
+        .unwrap();
+        Self { dir }
+    }
+
+    fn path(&self, file: &str) -> String {
+        self.dir.join(file).to_string_lossy().into_owned()
+    }
+
+    fn write(&self, file: &str, content: &str) {
+        fs::write(self.dir.join(file), content).unwrap();
+    }
+
+    fn run(&self, tool_name: &str, tool_input: Value) -> (i32, String) {
+        let input = json!({
+            "session_id": "test",
+            "cwd": self.dir,
+            "hook_event_name": "PostToolUse",
+            "tool_name": tool_name,
+            "tool_input": tool_input,
+        });
+        let mut child = Command::new(env!("CARGO_BIN_EXE_claude-decomment-hook"))
+            .env("XDG_CONFIG_HOME", &self.dir)
+            .env_remove("DECOMMENT_DISABLE")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(input.to_string().as_bytes())
+            .unwrap();
+        let output = child.wait_with_output().unwrap();
+        let mut messages = String::from_utf8_lossy(&output.stdout).into_owned();
+        messages.push_str(&String::from_utf8_lossy(&output.stderr));
+        (output.status.code().unwrap(), messages)
+    }
+
+    fn edit(&self, file: &str, old_string: &str, new_string: &str) -> (i32, String) {
+        self.run(
+            "Edit",
+            json!({
+                "file_path": self.path(file),
+                "old_string": old_string,
+                "new_string": new_string,
+            }),
+        )
+    }
+}
+
+impl Drop for Project {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+#[test]
+fn edit_honours_a_marker_already_in_old_string() {
+    let project = Project::new("edit-marker", "");
+    let new_string = "# decomment: allow-next -- why\n# explains magic\nx = 1";
+    project.write("app.py", &format!("{}\n", new_string));
+    let (code, _) = project.edit(
+        "app.py",
+        "# decomment: allow-next -- why\nx = 1",
+        new_string,
+    );
+    assert_eq!(code, EXIT_PASS);
+}
+
+#[test]
+fn multi_edit_markers_do_not_cross_edits() {
+    let project = Project::new("multi-edit-marker", "");
+    let first = "# decomment: allow-next -- why\n# retry the request\na = 1";
+    let second = "b = 2\n# retry the request";
+    project.write("app.py", &format!("{}\n{}\n", first, second));
+    let (code, message) = project.run(
+        "MultiEdit",
+        json!({
+            "file_path": project.path("app.py"),
+            "edits": [
+                {"old_string": "a = 1", "new_string": first},
+                {"old_string": "b = 2", "new_string": second},
+            ],
+        }),
+    );
+    assert_eq!(code, EXIT_BLOCK);
+    assert!(message.contains("# retry the request"));
+}
+
+#[test]
+fn edit_sees_ts_check_at_the_top_of_the_file() {
+    let project = Project::new("edit-ts-check", "include_docstrings = true\n");
+    let new_string = "/** @type {number} */\nconst x = 1;";
+    let file = format!("// @ts-check\nconst a = 1;\n{}\n", new_string);
+    project.write("app.js", &file);
+    let (code, _) = project.edit("app.js", "const x = 1;", new_string);
+    assert_eq!(code, EXIT_PASS);
+    let (code, _) = project.run(
+        "Write",
+        json!({"file_path": project.path("app.js"), "content": file}),
+    );
+    assert_eq!(code, EXIT_PASS);
+}
+
+#[test]
+fn edit_and_write_agree_on_private_docstrings() {
+    let project = Project::new(
+        "edit-visibility",
+        "include_docstrings = true\n[languages.python]\npublic_docs = \"allow\"\nprivate_docs = \"docstring\"\n",
+    );
+    let new_string = "    def run(self):\n        \"\"\"Run it.\"\"\"\n        return 1";
+    let file = format!("class _Private:\n{}\n", new_string);
+    project.write("app.py", &file);
+    let (code, message) =
+        project.edit("app.py", "    def run(self):\n        return 1", new_string);
+    assert_eq!(code, EXIT_BLOCK);
+    assert!(message.contains("Run it."));
+    let (code, _) = project.run(
+        "Write",
+        json!({"file_path": project.path("app.py"), "content": file}),
+    );
+    assert_eq!(code, EXIT_BLOCK);
+}